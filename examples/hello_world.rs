@@ -3,7 +3,9 @@
 
 use env_logger;
 #[cfg(target_os = "macos")]
-use menubar::appkit::{InitializedApplication, MenuBar, MenuItemState, NSMenu, NSMenuItem};
+use menubar::appkit::{
+    InitializedApplication, MainThreadMarker, MenuBar, MenuItemState, NSMenu, NSMenuItem,
+};
 #[cfg(target_os = "macos")]
 use objc2::{
     class, msg_send,
@@ -104,14 +106,14 @@ fn main() -> Result<(), Box<dyn Error>> {
 
         menubar.add("menu hidden", |menu| {
             let item = NSMenuItem::new("item 1", "g", None);
-            assert!(!item.hidden());
+            assert!(!item.is_hidden());
             menu.add(item);
             let mut item = NSMenuItem::new("item 2", "h", None);
-            assert!(!item.hidden());
+            assert!(!item.is_hidden());
             item.set_hidden(true);
-            assert!(item.hidden());
+            assert!(item.is_hidden());
             item.set_hidden(false);
-            assert!(!item.hidden());
+            assert!(!item.is_hidden());
             menu.add(item);
             let mut item = NSMenuItem::new("item 3", "i", None);
             item.set_hidden(true);
@@ -345,13 +347,14 @@ fn main() -> Result<(), Box<dyn Error>> {
                 dbg!("Init");
                 #[cfg(target_os = "macos")]
                 {
-                    let app = unsafe { InitializedApplication::new() };
+                    let app =
+                        unsafe { InitializedApplication::new(MainThreadMarker::new().unwrap()) };
                     app.set_window_menu(&window_menu);
                     app.set_services_menu(&services_menu);
                     app.set_help_menu(Some(&help_menu));
 
                     let menubar = app.set_menubar(menubar.take().unwrap());
-                    autoreleasepool(|pool| assert_eq!(&*menubar, app.menubar(pool).unwrap()));
+                    autoreleasepool(|pool| assert_eq!(menubar.root(), app.menubar(pool).unwrap()));
                 }
             }
             Event::WindowEvent {
@@ -369,7 +372,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             } => {
                 #[cfg(target_os = "macos")]
                 {
-                    let app = unsafe { InitializedApplication::new() };
+                    let app =
+                        unsafe { InitializedApplication::new(MainThreadMarker::new().unwrap()) };
                     if state == ElementState::Pressed {
                         app.set_menubar_visible(true);
                         dbg!(app.menubar_visible());
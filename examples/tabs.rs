@@ -1,5 +1,5 @@
 #[cfg(target_os = "macos")]
-use menubar::appkit::{InitializedApplication, MenuBar, NSMenuItem};
+use menubar::appkit::{InitializedApplication, MainThreadMarker, MenuBar, NSMenuItem};
 #[cfg(target_os = "macos")]
 use objc2::rc::autoreleasepool;
 use std::collections::HashMap;
@@ -33,7 +33,9 @@ fn main() {
                 #[cfg(target_os = "macos")]
                 {
                     autoreleasepool(|_pool| {
-                        let app = unsafe { InitializedApplication::new() };
+                        let app = unsafe {
+                            InitializedApplication::new(MainThreadMarker::new().unwrap())
+                        };
                         // let menubar = app.menubar(pool).unwrap();
                         // // Yeah, this is not ok but we'll do it for now
                         // let menubar: Id<NSMenu, Owned> =
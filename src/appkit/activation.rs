@@ -0,0 +1,118 @@
+//! Observing whether the application is active.
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr;
+use std::sync::Once;
+
+use objc2::declare::ClassBuilder;
+use objc2::rc::{Id, Owned};
+use objc2::runtime::{Bool, Class, Object, Sel};
+use objc2::{class, msg_send, sel};
+use objc2_foundation::NSString;
+
+const CLASS_NAME: &str = "MenubarActivationObserver";
+const CALLBACK_IVAR: &str = "_callback";
+
+extern "C" {
+    static NSApplicationDidBecomeActiveNotification: &'static NSString;
+    static NSApplicationDidResignActiveNotification: &'static NSString;
+}
+
+type Callback = Box<dyn Fn(bool)>;
+
+fn class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut builder = ClassBuilder::new(CLASS_NAME, class!(NSObject)).unwrap();
+        builder.add_ivar::<*mut c_void>(CALLBACK_IVAR);
+        unsafe {
+            builder.add_method(
+                sel!(activationChanged:),
+                activation_changed as extern "C" fn(&Object, Sel, *mut Object),
+            );
+            builder.add_method(sel!(dealloc), dealloc as extern "C" fn(&mut Object, Sel));
+        }
+        builder.register();
+    });
+    Class::get(CLASS_NAME).unwrap()
+}
+
+fn notification_center() -> *mut Object {
+    unsafe { msg_send![class!(NSNotificationCenter), defaultCenter] }
+}
+
+extern "C" fn activation_changed(this: &Object, _cmd: Sel, _notification: *mut Object) {
+    let callback: *mut c_void = unsafe { *this.ivar(CALLBACK_IVAR) };
+    let callback = match unsafe { callback.cast::<Callback>().as_ref() } {
+        Some(callback) => callback,
+        None => return,
+    };
+    // Read the state instead of the notification's name, the notifications
+    // are posted after it has changed
+    let app: *mut Object = unsafe { msg_send![class!(NSApplication), sharedApplication] };
+    let active: Bool = unsafe { msg_send![app, isActive] };
+    callback(active.is_true());
+}
+
+extern "C" fn dealloc(this: &mut Object, _cmd: Sel) {
+    let callback: *mut c_void = unsafe { *this.ivar(CALLBACK_IVAR) };
+    if !callback.is_null() {
+        drop(unsafe { Box::from_raw(callback.cast::<Callback>()) });
+    }
+    let _: () = unsafe { msg_send![super(this, class!(NSObject)), dealloc] };
+}
+
+/// Calls a closure when the application becomes active or inactive, until
+/// dropped.
+///
+/// Created with [`InitializedApplication::on_activation_change`].
+///
+/// [`InitializedApplication::on_activation_change`]: super::InitializedApplication::on_activation_change
+#[must_use = "The callback is removed when the observer is dropped"]
+pub struct ActivationObserver {
+    observer: Id<Object, Owned>,
+}
+
+impl ActivationObserver {
+    pub(super) fn new(f: impl Fn(bool) + 'static) -> Self {
+        let callback: Box<Callback> = Box::new(Box::new(f));
+        let mut observer: Id<Object, Owned> = unsafe {
+            let ptr: *mut Object = msg_send![class(), alloc];
+            Id::new(msg_send![ptr, init]).unwrap()
+        };
+        unsafe { observer.set_ivar::<*mut c_void>(CALLBACK_IVAR, Box::into_raw(callback).cast()) };
+
+        let object: *const Object = ptr::null();
+        for name in unsafe {
+            [
+                NSApplicationDidBecomeActiveNotification,
+                NSApplicationDidResignActiveNotification,
+            ]
+        } {
+            let _: () = unsafe {
+                msg_send![
+                    notification_center(),
+                    addObserver: &*observer,
+                    selector: sel!(activationChanged:),
+                    name: name,
+                    object: object,
+                ]
+            };
+        }
+        Self { observer }
+    }
+}
+
+impl Drop for ActivationObserver {
+    fn drop(&mut self) {
+        let _: () = unsafe { msg_send![notification_center(), removeObserver: &*self.observer] };
+    }
+}
+
+impl fmt::Debug for ActivationObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActivationObserver")
+            .field("id", &(&*self.observer as *const Object))
+            .finish()
+    }
+}
@@ -0,0 +1,34 @@
+use objc2::runtime::Object;
+use objc2::{class, msg_send};
+use objc2_foundation::NSString;
+
+/// The appearance a menu is drawn with.
+#[doc(alias = "NSAppearance")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Appearance {
+    /// The light appearance.
+    #[doc(alias = "NSAppearanceNameAqua")]
+    Aqua,
+    /// The dark appearance.
+    #[doc(alias = "NSAppearanceNameDarkAqua")]
+    DarkAqua,
+    /// Follow the system setting (or the appearance of the application, if
+    /// it overrides it). This is the default.
+    System,
+}
+
+impl Appearance {
+    /// The `NSAppearance` object for this appearance, or null for
+    /// [`Appearance::System`].
+    pub(super) fn to_raw(self) -> *mut Object {
+        // The appearance name constants are not available before macOS
+        // 10.14, so avoid linking to them; their values are their names.
+        let name = match self {
+            Self::Aqua => "NSAppearanceNameAqua",
+            Self::DarkAqua => "NSAppearanceNameDarkAqua",
+            Self::System => return core::ptr::null_mut(),
+        };
+        let name = NSString::from_str(name);
+        unsafe { msg_send![class!(NSAppearance), appearanceNamed: &*name] }
+    }
+}
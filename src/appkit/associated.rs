@@ -0,0 +1,38 @@
+use core::ffi::c_void;
+use objc2::ffi;
+use objc2::runtime::Object;
+
+/// Make `owner` retain `value` under `key`, releasing any previous value.
+///
+/// Used to keep objects alive that AppKit only holds weakly (like targets
+/// and delegates) for as long as their owner lives.
+///
+/// # Safety
+///
+/// `owner` must be a valid Objective-C object.
+pub(super) unsafe fn set(owner: *const Object, key: &'static u8, value: Option<&Object>) {
+    let value = value.map_or(core::ptr::null_mut(), |v| v as *const Object as *mut Object);
+    unsafe {
+        ffi::objc_setAssociatedObject(
+            owner as *mut ffi::objc_object,
+            key as *const u8 as *const c_void,
+            value.cast(),
+            ffi::OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+        )
+    }
+}
+
+/// Get the value previously set with [`set`].
+///
+/// # Safety
+///
+/// `owner` must be a valid Objective-C object.
+pub(super) unsafe fn get(owner: *const Object, key: &'static u8) -> *mut Object {
+    let value = unsafe {
+        ffi::objc_getAssociatedObject(
+            owner as *const ffi::objc_object,
+            key as *const u8 as *const c_void,
+        )
+    };
+    value as *mut Object
+}
@@ -0,0 +1,145 @@
+use core::ops::{Bound, Range, RangeBounds};
+use objc2::rc::{Id, Owned, Shared};
+use objc2::runtime::Object;
+use objc2::{msg_send, sel};
+use objc2_foundation::{
+    NSAttributedString, NSCopying, NSMutableAttributedString, NSRange, NSString,
+};
+
+use super::color::NSColor;
+use super::font::NSFont;
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    pub(super) static NSForegroundColorAttributeName: &'static NSString;
+    pub(super) static NSFontAttributeName: &'static NSString;
+}
+
+/// A minimal builder for styled text, for use with
+/// [`NSMenuItem::set_attributed_title`].
+///
+/// Ranges are byte ranges into the text, and must lie on character
+/// boundaries.
+///
+/// # Example
+///
+/// ```no_run
+/// use menubar::appkit::{AttributedStringBuilder, NSColor, NSMenuItem};
+///
+/// let name = "notes.txt";
+/// let text = format!("{} ~/Documents", name);
+/// let title = AttributedStringBuilder::new(&text)
+///     .color(name.len().., &NSColor::secondary_label())
+///     .build();
+///
+/// let mut item = NSMenuItem::new(name, "", None);
+/// item.set_attributed_title(Some(&title));
+/// ```
+///
+/// [`NSMenuItem::set_attributed_title`]: super::NSMenuItem::set_attributed_title
+#[derive(Debug)]
+pub struct AttributedStringBuilder {
+    text: String,
+    string: Id<NSMutableAttributedString, Owned>,
+}
+
+impl AttributedStringBuilder {
+    /// Start building an attributed string with the given text and no
+    /// attributes.
+    pub fn new(text: &str) -> Self {
+        let string = NSMutableAttributedString::from_nsstring(&NSString::from_str(text));
+        Self {
+            text: text.to_owned(),
+            string,
+        }
+    }
+
+    /// Set the color of the text in the given range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or not on character boundaries.
+    pub fn color(self, range: impl RangeBounds<usize>, color: &NSColor) -> Self {
+        let name = unsafe { NSForegroundColorAttributeName };
+        self.add_attribute(name, color as *const NSColor as *const Object, range)
+    }
+
+    /// Set the font of the text in the given range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or not on character boundaries.
+    pub fn font(self, range: impl RangeBounds<usize>, font: &NSFont) -> Self {
+        let name = unsafe { NSFontAttributeName };
+        self.add_attribute(name, font as *const NSFont as *const Object, range)
+    }
+
+    fn add_attribute(
+        mut self,
+        name: &NSString,
+        value: *const Object,
+        range: impl RangeBounds<usize>,
+    ) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.text.len(),
+        };
+        let range = self.utf16_range(start..end);
+        let _: () =
+            unsafe { msg_send![&mut *self.string, addAttribute: name, value: value, range: range] };
+        self
+    }
+
+    /// Convert a byte range to the UTF-16 range used by `NSString`.
+    fn utf16_range(&self, range: Range<usize>) -> NSRange {
+        let start = self.text[..range.start].encode_utf16().count();
+        let length = self.text[range].encode_utf16().count();
+        NSRange::from(start..start + length)
+    }
+
+    /// Finish building the attributed string.
+    pub fn build(self) -> Id<NSAttributedString, Shared> {
+        self.string.copy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::rc::autoreleasepool;
+
+    #[test]
+    fn test_build() {
+        autoreleasepool(|pool| {
+            let text = "abcαβγ 🐱";
+            let string = AttributedStringBuilder::new(text)
+                .color(.."abc".len(), &NSColor::secondary_label())
+                .font("abc".len().., &NSFont::system_font_of_size(20.0))
+                .font(.., &NSFont::menu_font_of_size(0.0))
+                .build();
+            assert_eq!(string.string().as_str(pool), text);
+        });
+    }
+
+    #[test]
+    fn test_utf16_range() {
+        let builder = AttributedStringBuilder::new("aβ🐱c");
+        assert_eq!(builder.utf16_range(0..1), NSRange::from(0..1));
+        assert_eq!(builder.utf16_range(1..3), NSRange::from(1..2));
+        // The emoji takes two UTF-16 code units
+        assert_eq!(builder.utf16_range(3..7), NSRange::from(2..4));
+        assert_eq!(builder.utf16_range(7..8), NSRange::from(4..5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_not_char_boundary() {
+        AttributedStringBuilder::new("β").color(0..1, &NSColor::label());
+    }
+}
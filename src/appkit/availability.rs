@@ -0,0 +1,46 @@
+//! Runtime checks for APIs only available on newer macOS versions, so that
+//! wrappers can degrade gracefully instead of crashing on older systems.
+use objc2::runtime::{Bool, Class, Object, Sel};
+use objc2::{msg_send, sel};
+
+/// Whether `obj` implements (or inherits) the method `sel`.
+pub(super) fn responds_to(obj: &Object, sel: Sel) -> bool {
+    let responds: Bool = unsafe { msg_send![obj, respondsToSelector: sel] };
+    responds.is_true()
+}
+
+/// Whether `cls` has the class method `sel`.
+pub(super) fn class_responds_to(cls: &Class, sel: Sel) -> bool {
+    // Classes are objects too, whose methods are the class methods
+    responds_to(unsafe { &*(cls as *const Class as *const Object) }, sel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::class;
+    use objc2::rc::{autoreleasepool, Id, Owned};
+
+    #[test]
+    fn test_responds_to() {
+        autoreleasepool(|_| {
+            let obj: Id<Object, Owned> = unsafe {
+                let ptr: *mut Object = msg_send![class!(NSObject), alloc];
+                Id::new(msg_send![ptr, init]).unwrap()
+            };
+            assert!(responds_to(&obj, sel!(description)));
+            assert!(!responds_to(&obj, sel!(menubarUnknownSelector)));
+            assert!(!responds_to(&obj, sel!(alloc)));
+        });
+    }
+
+    #[test]
+    fn test_class_responds_to() {
+        assert!(class_responds_to(class!(NSObject), sel!(alloc)));
+        assert!(!class_responds_to(
+            class!(NSObject),
+            sel!(menubarUnknownSelector:)
+        ));
+        assert!(!class_responds_to(class!(NSObject), sel!(description:)));
+    }
+}
@@ -0,0 +1,88 @@
+use core::fmt;
+use objc2::rc::{Id, Shared};
+use objc2::{class, msg_send, sel};
+use objc2::{Encoding, Message, RefEncode};
+use objc2_foundation::CGFloat;
+
+/// A thin wrapper around a color, for use in attributed titles.
+#[repr(C)]
+pub struct NSColor {
+    _priv: [u8; 0],
+}
+
+unsafe impl RefEncode for NSColor {
+    const ENCODING_REF: Encoding<'static> = Encoding::Object;
+}
+
+unsafe impl Message for NSColor {}
+
+unsafe impl Send for NSColor {}
+unsafe impl Sync for NSColor {}
+
+impl NSColor {
+    /// A color in the sRGB color space, with components between `0.0` and
+    /// `1.0`.
+    #[doc(alias = "colorWithSRGBRed")]
+    #[doc(alias = "colorWithSRGBRed:green:blue:alpha:")]
+    pub fn from_srgb(red: f64, green: f64, blue: f64, alpha: f64) -> Id<Self, Shared> {
+        unsafe {
+            let ptr: *mut Self = msg_send![
+                class!(NSColor),
+                colorWithSRGBRed: red as CGFloat,
+                green: green as CGFloat,
+                blue: blue as CGFloat,
+                alpha: alpha as CGFloat,
+            ];
+            Id::retain_autoreleased(ptr).unwrap()
+        }
+    }
+
+    /// The color of primary text, adapting to light and dark mode.
+    #[doc(alias = "labelColor")]
+    pub fn label() -> Id<Self, Shared> {
+        unsafe {
+            let ptr: *mut Self = msg_send![class!(NSColor), labelColor];
+            Id::retain_autoreleased(ptr).unwrap()
+        }
+    }
+
+    /// The color of dimmed, secondary text, adapting to light and dark mode.
+    #[doc(alias = "secondaryLabelColor")]
+    pub fn secondary_label() -> Id<Self, Shared> {
+        unsafe {
+            let ptr: *mut Self = msg_send![class!(NSColor), secondaryLabelColor];
+            Id::retain_autoreleased(ptr).unwrap()
+        }
+    }
+}
+
+impl PartialEq for NSColor {
+    /// Pointer equality
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NSColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSColor")
+            .field("id", &(self as *const Self))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::rc::autoreleasepool;
+
+    #[test]
+    fn test_colors() {
+        autoreleasepool(|_| {
+            let _ = NSColor::from_srgb(1.0, 0.5, 0.0, 1.0);
+            let _ = NSColor::label();
+            let _ = NSColor::secondary_label();
+        });
+    }
+}
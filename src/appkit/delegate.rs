@@ -0,0 +1,453 @@
+use core::cell::RefCell;
+use core::ffi::c_void;
+use core::fmt;
+use core::mem;
+use std::sync::Once;
+
+use objc2::declare::ClassBuilder;
+use objc2::ffi::NSInteger;
+use objc2::rc::{Id, Owned};
+use objc2::runtime::{Bool, Class, Object, Protocol, Sel};
+use objc2::{class, msg_send, sel};
+use objc2_foundation::{CGFloat, NSPoint, NSRect, NSSize};
+
+use super::associated;
+use super::menu::NSMenu;
+use super::menuitem::NSMenuItem;
+
+const CLASS_NAME: &str = "MenubarMenuDelegate";
+const CALLBACKS_IVAR: &str = "_callbacks";
+
+/// Key used to associate the delegate with the menu, since menus don't
+/// retain their delegate.
+pub(super) static ASSOCIATION_KEY: u8 = 0;
+
+type MenuCallback = Box<dyn Fn(&NSMenu)>;
+type CountCallback = Box<dyn Fn() -> usize>;
+type UpdateCallback = Box<dyn Fn(&NSMenu, &mut NSMenuItem, usize) -> bool>;
+type HighlightCallback = Box<dyn Fn(Option<&NSMenuItem>)>;
+type ConfinementCallback = Box<dyn Fn() -> Option<(f64, f64, f64, f64)>>;
+
+/// Callbacks for events on a menu, installed with [`NSMenu::set_delegate`].
+///
+/// The callbacks are kept alive for as long as the menu is.
+#[doc(alias = "NSMenuDelegate")]
+#[derive(Default)]
+pub struct MenuDelegate {
+    will_open: Option<MenuCallback>,
+    did_close: Option<MenuCallback>,
+    populate: Option<(CountCallback, UpdateCallback)>,
+    will_highlight: Option<HighlightCallback>,
+    confinement: Option<ConfinementCallback>,
+    observers: Vec<MenuObserver>,
+}
+
+impl MenuDelegate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called just before the menu is opened.
+    ///
+    /// The menu is only borrowed immutably, since it can be referenced
+    /// elsewhere while it opens. Use [`on_populate`](Self::on_populate) to
+    /// fill the menu just before it is shown.
+    #[doc(alias = "menuWillOpen")]
+    #[doc(alias = "menuWillOpen:")]
+    pub fn on_open(mut self, f: impl Fn(&NSMenu) + 'static) -> Self {
+        self.will_open = Some(Box::new(f));
+        self
+    }
+
+    /// Called after the menu has been closed.
+    #[doc(alias = "menuDidClose")]
+    #[doc(alias = "menuDidClose:")]
+    pub fn on_close(mut self, f: impl Fn(&NSMenu) + 'static) -> Self {
+        self.did_close = Some(Box::new(f));
+        self
+    }
+
+    /// Called when the highlighted item is about to change, with the item
+    /// to be highlighted, or `None` if the highlight is cleared.
+    ///
+    /// Useful for live previews of what the item would do.
+    ///
+    /// See also [`NSMenu::set_highlight_handler`].
+    #[doc(alias = "menu:willHighlightItem:")]
+    pub fn on_highlight(mut self, f: impl Fn(Option<&NSMenuItem>) + 'static) -> Self {
+        self.will_highlight = Some(Box::new(f));
+        self
+    }
+
+    pub(super) fn set_observers(&mut self, observers: Vec<MenuObserver>) {
+        self.observers = observers;
+    }
+
+    pub(super) fn set_highlight(&mut self, f: impl Fn(Option<&NSMenuItem>) + 'static) {
+        self.will_highlight = Some(Box::new(f));
+    }
+
+    /// Constrain where on the screen the menu may be shown.
+    ///
+    /// Called when the menu is about to be shown, and should return the
+    /// rectangle `(x, y, width, height)`, in screen coordinates, that the
+    /// menu must stay within. Return `None` to use the default, which is
+    /// the screen the menu is shown on.
+    ///
+    /// This is useful to keep e.g. context menus on a specific screen in
+    /// multi-monitor setups.
+    ///
+    /// See also [`NSMenu::set_confinement_handler`].
+    #[doc(alias = "confinementRectForMenu")]
+    #[doc(alias = "confinementRectForMenu:onScreen:")]
+    pub fn on_confine(mut self, f: impl Fn() -> Option<(f64, f64, f64, f64)> + 'static) -> Self {
+        self.set_confinement(f);
+        self
+    }
+
+    pub(super) fn set_confinement(
+        &mut self,
+        f: impl Fn() -> Option<(f64, f64, f64, f64)> + 'static,
+    ) {
+        self.confinement = Some(Box::new(f));
+    }
+
+    /// Populate the menu lazily, only creating the items that are actually
+    /// displayed.
+    ///
+    /// When the menu is about to be shown, AppKit calls `count` and resizes
+    /// the menu to that many (empty) items. `update` is then called with
+    /// the index of each item as it becomes visible, and a blank item on
+    /// which to set the title, action and so on; these are then moved over
+    /// to the item in the menu. Return `false` from `update` to stop updating the
+    /// remaining items.
+    ///
+    /// `update` is also passed the menu being updated, e.g. to check
+    /// [`NSMenu::properties_to_update`].
+    ///
+    /// See also [`NSMenu::set_lazy_populator`].
+    #[doc(alias = "numberOfItemsInMenu")]
+    #[doc(alias = "numberOfItemsInMenu:")]
+    #[doc(alias = "menu:updateItem:atIndex:shouldCancel:")]
+    pub fn on_populate(
+        mut self,
+        count: impl Fn() -> usize + 'static,
+        update: impl Fn(&NSMenu, &mut NSMenuItem, usize) -> bool + 'static,
+    ) -> Self {
+        self.set_populate(count, update);
+        self
+    }
+
+    pub(super) fn set_populate(
+        &mut self,
+        count: impl Fn() -> usize + 'static,
+        update: impl Fn(&NSMenu, &mut NSMenuItem, usize) -> bool + 'static,
+    ) {
+        self.populate = Some((Box::new(count), Box::new(update)));
+    }
+}
+
+impl fmt::Debug for MenuDelegate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MenuDelegate")
+            .field("on_open", &self.will_open.is_some())
+            .field("on_close", &self.did_close.is_some())
+            .field("on_populate", &self.populate.is_some())
+            .field("on_highlight", &self.will_highlight.is_some())
+            .field("on_confine", &self.confinement.is_some())
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+/// Callbacks for a menu that can coexist with other observers and the
+/// menu's delegate, added with [`NSMenu::add_delegate_observer`].
+///
+/// Only the events that don't need a single answer can be observed;
+/// populating and confining the menu must be done through
+/// [`MenuDelegate`].
+#[derive(Default)]
+pub struct MenuObserver {
+    will_open: Option<MenuCallback>,
+    did_close: Option<MenuCallback>,
+    will_highlight: Option<HighlightCallback>,
+}
+
+impl MenuObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called just before the menu is opened, see [`MenuDelegate::on_open`].
+    #[doc(alias = "menuWillOpen")]
+    #[doc(alias = "menuWillOpen:")]
+    pub fn on_open(mut self, f: impl Fn(&NSMenu) + 'static) -> Self {
+        self.will_open = Some(Box::new(f));
+        self
+    }
+
+    /// Called after the menu has been closed, see
+    /// [`MenuDelegate::on_close`].
+    #[doc(alias = "menuDidClose")]
+    #[doc(alias = "menuDidClose:")]
+    pub fn on_close(mut self, f: impl Fn(&NSMenu) + 'static) -> Self {
+        self.did_close = Some(Box::new(f));
+        self
+    }
+
+    /// Called when the highlighted item is about to change, see
+    /// [`MenuDelegate::on_highlight`].
+    #[doc(alias = "menu:willHighlightItem:")]
+    pub fn on_highlight(mut self, f: impl Fn(Option<&NSMenuItem>) + 'static) -> Self {
+        self.will_highlight = Some(Box::new(f));
+        self
+    }
+}
+
+impl fmt::Debug for MenuObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MenuObserver")
+            .field("on_open", &self.will_open.is_some())
+            .field("on_close", &self.did_close.is_some())
+            .field("on_highlight", &self.will_highlight.is_some())
+            .finish()
+    }
+}
+
+fn class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut builder = ClassBuilder::new(CLASS_NAME, class!(NSObject)).unwrap();
+        builder.add_ivar::<*mut c_void>(CALLBACKS_IVAR);
+        if let Some(protocol) = Protocol::get("NSMenuDelegate") {
+            builder.add_protocol(protocol);
+        }
+        unsafe {
+            builder.add_method(
+                sel!(menuWillOpen:),
+                menu_will_open as extern "C" fn(&Object, Sel, *mut NSMenu),
+            );
+            builder.add_method(
+                sel!(menuDidClose:),
+                menu_did_close as extern "C" fn(&Object, Sel, *mut NSMenu),
+            );
+            builder.add_method(
+                sel!(menu:willHighlightItem:),
+                menu_will_highlight_item
+                    as extern "C" fn(&Object, Sel, *mut NSMenu, *mut NSMenuItem),
+            );
+            builder.add_method(
+                sel!(confinementRectForMenu:onScreen:),
+                confinement_rect as extern "C" fn(&Object, Sel, *mut NSMenu, *mut Object) -> NSRect,
+            );
+            builder.add_method(
+                sel!(numberOfItemsInMenu:),
+                number_of_items as extern "C" fn(&Object, Sel, *mut NSMenu) -> NSInteger,
+            );
+            builder.add_method(
+                sel!(menu:updateItem:atIndex:shouldCancel:),
+                update_item
+                    as extern "C" fn(
+                        &Object,
+                        Sel,
+                        *mut NSMenu,
+                        *mut NSMenuItem,
+                        NSInteger,
+                        Bool,
+                    ) -> Bool,
+            );
+            builder.add_method(sel!(dealloc), dealloc as extern "C" fn(&mut Object, Sel));
+        }
+        builder.register();
+    });
+    Class::get(CLASS_NAME).unwrap()
+}
+
+fn callbacks(this: &Object) -> Option<&RefCell<MenuDelegate>> {
+    let callbacks: *mut c_void = unsafe { *this.ivar(CALLBACKS_IVAR) };
+    unsafe { callbacks.cast::<RefCell<MenuDelegate>>().as_ref() }
+}
+
+extern "C" fn menu_will_open(this: &Object, _cmd: Sel, menu: *mut NSMenu) {
+    let callbacks = match callbacks(this) {
+        Some(callbacks) => callbacks.borrow(),
+        None => return,
+    };
+    let menu = match unsafe { menu.as_ref() } {
+        Some(menu) => menu,
+        None => return,
+    };
+    let observers = callbacks.observers.iter().map(|o| &o.will_open);
+    for f in core::iter::once(&callbacks.will_open)
+        .chain(observers)
+        .flatten()
+    {
+        f(menu);
+    }
+}
+
+extern "C" fn menu_did_close(this: &Object, _cmd: Sel, menu: *mut NSMenu) {
+    let callbacks = match callbacks(this) {
+        Some(callbacks) => callbacks.borrow(),
+        None => return,
+    };
+    let menu = match unsafe { menu.as_ref() } {
+        Some(menu) => menu,
+        None => return,
+    };
+    let observers = callbacks.observers.iter().map(|o| &o.did_close);
+    for f in core::iter::once(&callbacks.did_close)
+        .chain(observers)
+        .flatten()
+    {
+        f(menu);
+    }
+}
+
+extern "C" fn menu_will_highlight_item(
+    this: &Object,
+    _cmd: Sel,
+    _menu: *mut NSMenu,
+    item: *mut NSMenuItem,
+) {
+    let callbacks = match callbacks(this) {
+        Some(callbacks) => callbacks.borrow(),
+        None => return,
+    };
+    let item = unsafe { item.as_ref() };
+    let observers = callbacks.observers.iter().map(|o| &o.will_highlight);
+    for f in core::iter::once(&callbacks.will_highlight)
+        .chain(observers)
+        .flatten()
+    {
+        f(item);
+    }
+}
+
+extern "C" fn confinement_rect(
+    this: &Object,
+    _cmd: Sel,
+    _menu: *mut NSMenu,
+    _screen: *mut Object,
+) -> NSRect {
+    let callbacks = match callbacks(this) {
+        Some(callbacks) => callbacks.borrow(),
+        None => return NSRect::ZERO,
+    };
+    match callbacks.confinement.as_ref().and_then(|f| f()) {
+        Some((x, y, width, height)) => NSRect::new(
+            NSPoint::new(x as CGFloat, y as CGFloat),
+            NSSize::new(width as CGFloat, height as CGFloat),
+        ),
+        // A zero rectangle means the default confinement
+        None => NSRect::ZERO,
+    }
+}
+
+extern "C" fn number_of_items(this: &Object, _cmd: Sel, _menu: *mut NSMenu) -> NSInteger {
+    let callbacks = match callbacks(this) {
+        Some(callbacks) => callbacks.borrow(),
+        None => return -1,
+    };
+    match &callbacks.populate {
+        Some((count, _)) => count() as NSInteger,
+        // A negative number leaves the menu's items unchanged
+        None => -1,
+    }
+}
+
+extern "C" fn update_item(
+    this: &Object,
+    _cmd: Sel,
+    menu: *mut NSMenu,
+    item: *mut NSMenuItem,
+    index: NSInteger,
+    should_cancel: Bool,
+) -> Bool {
+    if should_cancel.is_true() {
+        return Bool::NO;
+    }
+    let callbacks = match callbacks(this) {
+        Some(callbacks) => callbacks.borrow(),
+        None => return Bool::NO,
+    };
+    let (update, menu, item) = match (&callbacks.populate, unsafe { menu.as_ref() }, unsafe {
+        item.as_ref()
+    }) {
+        (Some((_, update)), Some(menu), Some(item)) => (update, menu, item),
+        _ => return Bool::NO,
+    };
+    // The item belongs to AppKit, and may be referenced elsewhere, so the
+    // callback updates a fresh item instead, whose properties are then
+    // moved over
+    let mut template = NSMenuItem::new_empty();
+    let should_continue = update(menu, &mut template, index as usize);
+    NSMenuItem::move_properties(template, item);
+    Bool::new(should_continue)
+}
+
+extern "C" fn dealloc(this: &mut Object, _cmd: Sel) {
+    let callbacks: *mut c_void = unsafe { *this.ivar(CALLBACKS_IVAR) };
+    if !callbacks.is_null() {
+        drop(unsafe { Box::from_raw(callbacks.cast::<RefCell<MenuDelegate>>()) });
+    }
+    let _: () = unsafe { msg_send![super(this, class!(NSObject)), dealloc] };
+}
+
+/// Create a new delegate object forwarding to the given callbacks.
+pub(super) fn new(callbacks: MenuDelegate) -> Id<Object, Owned> {
+    let callbacks = Box::new(RefCell::new(callbacks));
+    let mut delegate: Id<Object, Owned> = unsafe {
+        let ptr: *mut Object = msg_send![class(), alloc];
+        Id::new(msg_send![ptr, init]).unwrap()
+    };
+    unsafe { delegate.set_ivar::<*mut c_void>(CALLBACKS_IVAR, Box::into_raw(callbacks).cast()) };
+    delegate
+}
+
+/// Modify the callbacks of the delegate installed on `menu` with
+/// [`NSMenu::set_delegate`], or install a new delegate if there is none.
+///
+/// # Panics
+///
+/// Panics if called from within one of the delegate's callbacks.
+pub(super) fn update(menu: &mut NSMenu, f: impl FnOnce(&mut MenuDelegate)) {
+    let menu_ptr = menu as *const NSMenu as *const Object;
+    let delegate = unsafe { associated::get(menu_ptr, &ASSOCIATION_KEY) };
+    match unsafe { delegate.as_ref() }.and_then(callbacks) {
+        Some(callbacks) => f(&mut callbacks
+            .try_borrow_mut()
+            .expect("Failed updating delegate: Cannot be modified from within its callbacks")),
+        None => {
+            let mut callbacks = MenuDelegate::new();
+            f(&mut callbacks);
+            menu.set_delegate(callbacks);
+        }
+    }
+}
+
+/// Take the observers from the delegate installed on `menu`, so that they
+/// can be carried over to a new delegate.
+///
+/// # Panics
+///
+/// Panics if called from within one of the delegate's callbacks.
+pub(super) fn take_observers(menu: &NSMenu) -> Vec<MenuObserver> {
+    let menu_ptr = menu as *const NSMenu as *const Object;
+    let delegate = unsafe { associated::get(menu_ptr, &ASSOCIATION_KEY) };
+    match unsafe { delegate.as_ref() }.and_then(callbacks) {
+        Some(callbacks) => mem::take(
+            &mut callbacks
+                .try_borrow_mut()
+                .expect("Failed replacing delegate: Cannot be replaced from within its callbacks")
+                .observers,
+        ),
+        None => Vec::new(),
+    }
+}
+
+/// Add an observer to the delegate installed on `menu`, or install a new
+/// delegate if there is none.
+pub(super) fn add_observer(menu: &mut NSMenu, observer: MenuObserver) {
+    update(menu, |callbacks| callbacks.observers.push(observer));
+}
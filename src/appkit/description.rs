@@ -0,0 +1,181 @@
+//! Data-driven menus, e.g. loaded from configuration or localization files.
+use objc2::rc::{Id, Owned};
+use serde::{Deserialize, Serialize};
+
+use super::keyequivalent::KeyModifiers;
+use super::menu::NSMenu;
+use super::menubar::MenuBar;
+use super::menuitem::NSMenuItem;
+use crate::strip_mnemonics;
+
+/// A serializable description of an entire menu bar.
+///
+/// Each entry in `menus` is a top-level menu, whose `items` are the contents
+/// of that menu. The first entry is the application menu.
+///
+/// Actions can't be serialized, so instead give items a `tag`, and look them
+/// up with [`NSMenu::item_with_tag`] to attach actions after building.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MenuDescription {
+    #[serde(default)]
+    pub menus: Vec<ItemDescription>,
+}
+
+/// A serializable description of a menu item.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemDescription {
+    /// The title, which may contain Windows-style `&` mnemonics, so that
+    /// the description can be shared between platforms. These are removed
+    /// with [`strip_mnemonics`] when building the menu.
+    ///
+    /// [`strip_mnemonics`]: crate::strip_mnemonics
+    pub title: String,
+    /// The key equivalent, e.g. `"n"`. Empty for none.
+    pub key_equivalent: String,
+    /// The modifiers of the key equivalent. Defaults to Command.
+    pub modifiers: Option<Vec<ModifierDescription>>,
+    /// Defaults to `true`. Only has an effect if the menu doesn't
+    /// automatically enable its items, see [`NSMenu::set_autoenables_items`].
+    pub enabled: Option<bool>,
+    /// Whether this is a separator, in which case all other fields are
+    /// ignored.
+    pub separator: bool,
+    /// Used by the application to identify the item, e.g. to dispatch
+    /// actions.
+    pub tag: Option<isize>,
+    /// The items of the submenu, if any.
+    pub items: Option<Vec<ItemDescription>>,
+}
+
+/// A serializable key equivalent modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModifierDescription {
+    Shift,
+    Control,
+    Option,
+    Command,
+    Function,
+}
+
+impl From<ModifierDescription> for KeyModifiers {
+    fn from(modifier: ModifierDescription) -> Self {
+        match modifier {
+            ModifierDescription::Shift => Self::SHIFT,
+            ModifierDescription::Control => Self::CONTROL,
+            ModifierDescription::Option => Self::OPTION,
+            ModifierDescription::Command => Self::COMMAND,
+            ModifierDescription::Function => Self::FUNCTION,
+        }
+    }
+}
+
+impl MenuDescription {
+    /// Build a new menu bar from the description.
+    pub fn build(&self) -> MenuBar {
+        let mut menus = self.menus.iter();
+        let mut menubar = MenuBar::new(|menu| {
+            if let Some(first) = menus.next() {
+                first.build_items(menu);
+            }
+        });
+        for description in menus {
+            let title = strip_mnemonics(&description.title);
+            let _ = menubar.add(&title, |menu| description.build_items(menu));
+        }
+        menubar
+    }
+}
+
+impl ItemDescription {
+    fn build_items(&self, menu: &mut NSMenu) {
+        for item in self.items.iter().flatten() {
+            let _ = menu.add(item.build());
+        }
+    }
+
+    fn build(&self) -> Id<NSMenuItem, Owned> {
+        if self.separator {
+            return NSMenuItem::new_separator();
+        }
+        let title = strip_mnemonics(&self.title);
+        let mut item = NSMenuItem::new(&title, "", None);
+        if !self.key_equivalent.is_empty() {
+            let modifiers = match &self.modifiers {
+                Some(modifiers) => modifiers
+                    .iter()
+                    .fold(KeyModifiers::empty(), |acc, &m| acc | m.into()),
+                None => KeyModifiers::COMMAND,
+            };
+            item.set_key_equivalent(&self.key_equivalent, modifiers);
+        }
+        item.set_enabled(self.enabled.unwrap_or(true));
+        if let Some(tag) = self.tag {
+            item.set_tag(tag);
+        }
+        if self.items.is_some() {
+            let mut submenu = NSMenu::new_with_title(&title);
+            self.build_items(&mut submenu);
+            let _ = item.set_submenu(Some(submenu));
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::rc::autoreleasepool;
+
+    #[test]
+    fn test_build() {
+        let description = MenuDescription {
+            menus: vec![
+                ItemDescription::default(),
+                ItemDescription {
+                    title: "&File".into(),
+                    items: Some(vec![
+                        ItemDescription {
+                            title: "Save &As…".into(),
+                            key_equivalent: "s".into(),
+                            modifiers: Some(vec![
+                                ModifierDescription::Command,
+                                ModifierDescription::Shift,
+                            ]),
+                            tag: Some(42),
+                            ..Default::default()
+                        },
+                        ItemDescription {
+                            separator: true,
+                            ..Default::default()
+                        },
+                        ItemDescription {
+                            title: "Recent".into(),
+                            items: Some(vec![]),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            ],
+        };
+        let menubar = description.build();
+        assert_eq!(menubar.title(1), "File");
+
+        let root = menubar.into_raw();
+        autoreleasepool(|pool| {
+            let file = root.item_at(pool, 1).unwrap().submenu(pool).unwrap();
+            assert_eq!(file.len(), 3);
+            let save_as = file.item_with_tag(pool, 42).unwrap();
+            assert_eq!(save_as.title(pool), "Save As…");
+            assert_eq!(
+                save_as.key_equivalent_modifier_mask(),
+                KeyModifiers::COMMAND | KeyModifiers::SHIFT
+            );
+            assert!(file.item_at(pool, 1).unwrap().is_separator());
+            let recent = file.item_at(pool, 2).unwrap().submenu(pool).unwrap();
+            assert!(recent.is_empty());
+        });
+    }
+}
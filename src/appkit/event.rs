@@ -0,0 +1,34 @@
+use core::fmt;
+use objc2::{Encoding, Message, RefEncode};
+
+/// A thin wrapper around an input event, like the mouse click that should
+/// show a context menu.
+///
+/// This crate doesn't create events itself; references can be obtained by
+/// casting a pointer from another crate.
+#[repr(C)]
+pub struct NSEvent {
+    _priv: [u8; 0],
+}
+
+unsafe impl RefEncode for NSEvent {
+    const ENCODING_REF: Encoding<'static> = Encoding::Object;
+}
+
+unsafe impl Message for NSEvent {}
+
+impl PartialEq for NSEvent {
+    /// Pointer equality
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NSEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSEvent")
+            .field("id", &(self as *const Self))
+            .finish()
+    }
+}
@@ -0,0 +1,86 @@
+use core::fmt;
+use objc2::rc::{Id, Shared};
+use objc2::{class, msg_send, sel};
+use objc2::{Encoding, Message, RefEncode};
+use objc2_foundation::CGFloat;
+
+/// A thin wrapper around a font, for use with [`NSMenu::set_font`].
+///
+/// [`NSMenu::set_font`]: super::NSMenu::set_font
+#[repr(C)]
+pub struct NSFont {
+    _priv: [u8; 0],
+}
+
+unsafe impl RefEncode for NSFont {
+    const ENCODING_REF: Encoding<'static> = Encoding::Object;
+}
+
+unsafe impl Message for NSFont {}
+
+unsafe impl Send for NSFont {}
+unsafe impl Sync for NSFont {}
+
+impl NSFont {
+    /// The standard system font in the given size (in points).
+    #[doc(alias = "systemFontOfSize")]
+    #[doc(alias = "systemFontOfSize:")]
+    pub fn system_font_of_size(size: f64) -> Id<Self, Shared> {
+        let size = size as CGFloat;
+        unsafe {
+            let ptr: *mut Self = msg_send![class!(NSFont), systemFontOfSize: size];
+            Id::retain_autoreleased(ptr).unwrap()
+        }
+    }
+
+    /// The font used for menu items, in the given size (in points).
+    ///
+    /// A size of `0.0` gives the default menu font size.
+    #[doc(alias = "menuFontOfSize")]
+    #[doc(alias = "menuFontOfSize:")]
+    pub fn menu_font_of_size(size: f64) -> Id<Self, Shared> {
+        let size = size as CGFloat;
+        unsafe {
+            let ptr: *mut Self = msg_send![class!(NSFont), menuFontOfSize: size];
+            Id::retain_autoreleased(ptr).unwrap()
+        }
+    }
+
+    #[doc(alias = "pointSize")]
+    pub fn point_size(&self) -> f64 {
+        let size: CGFloat = unsafe { msg_send![self, pointSize] };
+        size as f64
+    }
+}
+
+impl PartialEq for NSFont {
+    /// Pointer equality
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NSFont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSFont")
+            .field("id", &(self as *const Self))
+            .field("point_size", &self.point_size())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::rc::autoreleasepool;
+
+    #[test]
+    fn test_size() {
+        autoreleasepool(|_| {
+            assert_eq!(NSFont::system_font_of_size(20.0).point_size(), 20.0);
+            assert_eq!(NSFont::menu_font_of_size(18.0).point_size(), 18.0);
+            assert!(NSFont::menu_font_of_size(0.0).point_size() > 0.0);
+        });
+    }
+}
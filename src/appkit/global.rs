@@ -1,12 +1,23 @@
 use core::cell::UnsafeCell;
 use core::marker::PhantomData;
+use core::mem;
+use std::sync::Once;
+
+use objc2::declare::ClassBuilder;
+use objc2::ffi::{self, NSInteger};
 use objc2::rc::{AutoreleasePool, Id, Owned, Shared};
-use objc2::runtime::{Bool, Class, Object};
+use objc2::runtime::{Bool, Class, Object, Sel};
 use objc2::{class, msg_send, sel};
 use objc2::{Encoding, Message, RefEncode};
+use objc2_foundation::{CGFloat, NSArray, NSString};
 
+use super::activation::ActivationObserver;
+use super::associated;
+use super::availability;
+use super::mainthread::MainThreadMarker;
 use super::menu::NSMenu;
-use super::menubar::MenuBar;
+use super::menubar::{MainMenu, MenuBar};
+use super::visibility::MenubarVisibilityObserver;
 
 /// Helper to make various functions on the global application object safe.
 #[doc(alias = "NSApp")]
@@ -25,9 +36,17 @@ unsafe impl RefEncode for InitializedApplication {
 }
 
 unsafe impl Message for InitializedApplication {}
-unsafe impl Sync for InitializedApplication {}
 
 impl InitializedApplication {
+    /// Get the application object.
+    ///
+    /// The application is only usable from the main thread, hence the
+    /// marker. The returned reference is not `Send`, so it can't be moved
+    /// to another thread either.
+    ///
+    /// The marker says nothing about whether the application has finished
+    /// launching though, so this is still unsafe.
+    ///
     /// # Safety
     ///
     /// This must not be called before `applicationDidFinishLaunching`.
@@ -35,23 +54,72 @@ impl InitializedApplication {
     /// In `winit`, this is at or after
     /// [`winit::event::StartCause::Init`] has been emitted.
     #[doc(alias = "sharedApplication")]
-    pub unsafe fn new() -> &'static Self {
+    pub unsafe fn new(_mtm: MainThreadMarker) -> &'static Self {
         msg_send![class!(NSApplication), sharedApplication]
     }
 
+    /// A marker for the main thread, which the application is bound to.
+    pub fn main_thread_marker(&self) -> MainThreadMarker {
+        // SAFETY: The application can only be accessed on the main thread
+        unsafe { MainThreadMarker::new_unchecked() }
+    }
+
     #[doc(alias = "mainMenu")]
     pub fn menubar<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenu> {
         unsafe { msg_send![self, mainMenu] }
     }
 
+    /// Whether a menu bar has been set, without needing an autorelease pool
+    /// like [`menubar`](Self::menubar).
+    #[doc(alias = "mainMenu")]
+    pub fn menubar_present(&self) -> bool {
+        let menu: *const NSMenu = unsafe { msg_send![self, mainMenu] };
+        !menu.is_null()
+    }
+
     /// Setting the menubar to `null` does not work properly, so we don't allow
     /// that functionality here!
+    ///
+    /// If a menu bar was already set, it is replaced and released (unless a
+    /// handle to it is held elsewhere). See [`replace_menubar`] for
+    /// replacing a menu bar that might be in use.
+    ///
+    /// The returned [`MainMenu`] can be used to modify the menu bar after it
+    /// has been set.
+    ///
+    /// [`replace_menubar`]: Self::replace_menubar
     #[doc(alias = "setMainMenu")]
     #[doc(alias = "setMainMenu:")]
-    pub fn set_menubar(&self, menubar: MenuBar) -> Id<NSMenu, Shared> {
-        let menu = menubar.into_raw();
-        let _: () = unsafe { msg_send![self, setMainMenu: &*menu] };
-        menu.into()
+    pub fn set_menubar(&self, menubar: MenuBar) -> MainMenu {
+        let root: Id<NSMenu, Shared> = menubar.into_raw().into();
+        let _: () = unsafe { msg_send![self, setMainMenu: &*root] };
+        MainMenu::new(root)
+    }
+
+    /// Swap out the entire menu bar, e.g. for a different menu bar per
+    /// window state.
+    ///
+    /// If one of the previous menu bar's menus is open, it is closed first,
+    /// since replacing a menu bar while it is being tracked leaves the open
+    /// menu detached. The previous menu bar is then released, unless a
+    /// handle to it is held elsewhere.
+    ///
+    /// The window, services and help menus are not carried over, and should
+    /// be set again if they were part of the previous menu bar.
+    pub fn replace_menubar(&self, menubar: MenuBar) -> MainMenu {
+        let previous: *const NSMenu = unsafe { msg_send![self, mainMenu] };
+        if let Some(previous) = unsafe { previous.as_ref() } {
+            previous.cancel_tracking_without_animation();
+            for item in 0..previous.len() {
+                let item: *const Object =
+                    unsafe { msg_send![previous, itemAtIndex: item as NSInteger] };
+                let submenu: *const NSMenu = unsafe { msg_send![item, submenu] };
+                if let Some(submenu) = unsafe { submenu.as_ref() } {
+                    submenu.cancel_tracking_without_animation();
+                }
+            }
+        }
+        self.set_menubar(menubar)
     }
 
     /// Returns the first menu set with [`set_window_menu`]
@@ -81,7 +149,62 @@ impl InitializedApplication {
         unsafe { msg_send![self, setWindowsMenu: menu] }
     }
 
-    /// Returns the first menu set with [`set_services_menu`]
+    /// Prevent AppKit from adding window management items to the menu bar
+    /// on its own, e.g. for applications that draw their own window UI.
+    ///
+    /// This counteracts the following AppKit behaviors:
+    /// - If no window menu is set, AppKit adopts a top-level menu titled
+    ///   "Window" as the window menu, and fills it with the list of windows
+    ///   and items like "Minimize". Here, the window menu role is instead
+    ///   claimed by an empty menu that is not part of the menu bar.
+    /// - On macOS 10.12 and newer, automatic window tabbing adds "Show Tab
+    ///   Bar" and related items to the window (or View) menu. This disables
+    ///   automatic window tabbing for all windows.
+    /// - AppKit adds an "Enter Full Screen" item to the window (or View)
+    ///   menu of applications with full screen capable windows. This is
+    ///   turned off with the `NSFullScreenMenuItemEverywhere` user default.
+    ///
+    /// Replaces any menu set with [`set_window_menu`], and should be called
+    /// before [`set_menubar`], since AppKit inspects the menu bar when it is
+    /// set.
+    ///
+    /// [`set_window_menu`]: Self::set_window_menu
+    /// [`set_menubar`]: Self::set_menubar
+    pub fn suppress_window_menu(&self) {
+        let menu = NSMenu::new();
+        // SAFETY: The application is a valid object
+        unsafe {
+            associated::set(
+                self as *const Self as *const Object,
+                &SUPPRESSED_WINDOW_MENU_KEY,
+                Some(&*(&*menu as *const NSMenu as *const Object)),
+            )
+        };
+        self.set_window_menu(&menu);
+
+        let cls = class!(NSWindow);
+        if availability::class_responds_to(cls, sel!(setAllowsAutomaticWindowTabbing:)) {
+            let _: () = unsafe { msg_send![cls, setAllowsAutomaticWindowTabbing: Bool::NO] };
+        }
+
+        let key = NSString::from_str("NSFullScreenMenuItemEverywhere");
+        unsafe {
+            let value: *mut Object = msg_send![class!(NSNumber), numberWithBool: Bool::NO];
+            let defaults: *mut Object =
+                msg_send![class!(NSDictionary), dictionaryWithObject: value, forKey: &*key];
+            let user_defaults: *mut Object =
+                msg_send![class!(NSUserDefaults), standardUserDefaults];
+            // Registered defaults are not persisted
+            let _: () = msg_send![user_defaults, registerDefaults: defaults];
+        }
+    }
+
+    /// Returns the menu most recently set with [`set_services_menu`].
+    ///
+    /// Note that this may not be the menu AppKit actually populates, see
+    /// [`set_services_menu`] for details.
+    ///
+    /// [`set_services_menu`]: Self::set_services_menu
     #[doc(alias = "servicesMenu")]
     pub fn services_menu<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenu> {
         unsafe { msg_send![self, servicesMenu] }
@@ -96,7 +219,15 @@ impl InitializedApplication {
     /// don't expose that functionality here.
     ///
     /// Additionally, you can sometimes have luck setting the services menu
-    /// more than once, but this is really flaky.
+    /// more than once, but this is really flaky: AppKit keeps filling the
+    /// first menu it was given with services, even though [`services_menu`]
+    /// reports the new one. There is no known way to reset this, so the
+    /// supported pattern is to set the services menu exactly once, and if
+    /// the menu bar is rebuilt, move that same menu into the new Services
+    /// item (after removing it from the old one) instead of creating a new
+    /// one. Setting the same menu again is harmless.
+    ///
+    /// [`services_menu`]: Self::services_menu
     #[doc(alias = "setServicesMenu")]
     #[doc(alias = "setServicesMenu:")]
     pub fn set_services_menu(&self, menu: &NSMenu) {
@@ -106,7 +237,34 @@ impl InitializedApplication {
         unsafe { msg_send![self, setServicesMenu: menu] }
     }
 
-    // TODO: registerServicesMenuSendTypes
+    /// Register the pasteboard types the application can send to and receive
+    /// from services.
+    ///
+    /// The Services menu only offers services that accept one of the `send`
+    /// types, or return one of the `return_types`. Types are given as
+    /// pasteboard type strings, which on modern macOS are UTIs such as:
+    ///
+    /// - `"public.utf8-plain-text"` for plain text.
+    /// - `"public.rtf"` for rich text.
+    /// - `"public.html"` for HTML.
+    /// - `"public.png"` and `"public.tiff"` for images.
+    /// - `"public.file-url"` and `"public.url"` for file and web URLs.
+    #[doc(alias = "registerServicesMenuSendTypes")]
+    #[doc(alias = "registerServicesMenuSendTypes:returnTypes:")]
+    pub fn register_services_menu_send_types(&self, send: &[&str], return_types: &[&str]) {
+        fn to_array(types: &[&str]) -> Id<NSArray<NSString, Shared>, Shared> {
+            NSArray::from_vec(types.iter().map(|s| NSString::from_str(s)).collect())
+        }
+        let send = to_array(send);
+        let return_types = to_array(return_types);
+        let _: () = unsafe {
+            msg_send![
+                self,
+                registerServicesMenuSendTypes: &*send,
+                returnTypes: &*return_types,
+            ]
+        };
+    }
 
     /// Get the menu that is currently assigned as the help menu, or `None` if the system is configured to autodetect this.
     #[doc(alias = "helpMenu")]
@@ -127,7 +285,74 @@ impl InitializedApplication {
         unsafe { msg_send![self, setHelpMenu: menu] }
     }
 
-    // TODO: applicationDockMenu (the application delegate should implement this function)
+    /// Prevent AppKit from treating a top-level menu titled "Help" as the
+    /// help menu, and adding the Spotlight help search field to it.
+    ///
+    /// The help menu role is claimed by an empty menu that is not part of
+    /// the menu bar, as recommended in [`set_help_menu`]. This replaces any
+    /// menu set with [`set_help_menu`].
+    ///
+    /// [`set_help_menu`]: Self::set_help_menu
+    pub fn suppress_help_menu(&self) {
+        let menu = NSMenu::new();
+        // SAFETY: The application is a valid object
+        unsafe {
+            associated::set(
+                self as *const Self as *const Object,
+                &SUPPRESSED_HELP_MENU_KEY,
+                Some(&*(&*menu as *const NSMenu as *const Object)),
+            )
+        };
+        self.set_help_menu(Some(&menu));
+    }
+
+    /// Set the menu shown when right-clicking the application's Dock icon.
+    ///
+    /// The menu is returned from the application delegate's
+    /// `applicationDockMenu:`. To cooperate with an existing delegate (e.g.
+    /// the one `winit` installs) rather than replacing it, the method is
+    /// added to the delegate's class at runtime. If the delegate already
+    /// implements `applicationDockMenu:` itself, that implementation is left
+    /// untouched and takes precedence. If there is no delegate, a minimal one
+    /// is installed.
+    #[doc(alias = "applicationDockMenu")]
+    #[doc(alias = "applicationDockMenu:")]
+    pub fn set_dock_menu(&self, menu: Option<&NSMenu>) {
+        let app = self as *const Self as *const Object;
+        let menu = menu.map(|menu| unsafe { &*(menu as *const NSMenu as *const Object) });
+        // SAFETY: The application is a valid object
+        unsafe { associated::set(app, &DOCK_MENU_KEY, menu) };
+
+        let delegate: *mut Object = unsafe { msg_send![self, delegate] };
+        if let Some(delegate) = unsafe { delegate.as_ref() } {
+            let cls = delegate.class();
+            let sel = sel!(applicationDockMenu:);
+            if !availability::responds_to(delegate, sel) {
+                let imp: unsafe extern "C" fn() = unsafe {
+                    mem::transmute(
+                        application_dock_menu
+                            as extern "C" fn(&Object, Sel, *mut Object) -> *mut NSMenu,
+                    )
+                };
+                unsafe {
+                    ffi::class_addMethod(
+                        cls as *const Class as *mut ffi::objc_class,
+                        sel.as_ptr() as *const ffi::objc_selector,
+                        Some(imp),
+                        b"@@:@\0".as_ptr() as *const _,
+                    )
+                };
+            }
+        } else {
+            let delegate: Id<Object, Owned> = unsafe {
+                let ptr: *mut Object = msg_send![dock_menu_delegate_class(), alloc];
+                Id::new(msg_send![ptr, init]).unwrap()
+            };
+            // The application does not retain its delegate
+            unsafe { associated::set(app, &DOCK_MENU_DELEGATE_KEY, Some(&delegate)) };
+            let _: () = unsafe { msg_send![self, setDelegate: &*delegate] };
+        }
+    }
 
     #[doc(alias = "menuBarVisible")]
     pub fn menubar_visible(&self) -> bool {
@@ -146,45 +371,142 @@ impl InitializedApplication {
         unsafe { msg_send![class!(NSMenu), setMenuBarVisible: visible] }
     }
 
-    // Only available on the global menu bar object
-    // #[doc(alias = "menuBarHeight")]
-    // pub fn global_height(&self) -> f64 {
-    //     let height: CGFloat = unsafe { msg_send![self, menuBarHeight] };
-    //     height
-    // }
-}
+    /// Hide the menu bar while running `f`, e.g. for a full screen
+    /// presentation, and restore its previous visibility afterwards.
+    ///
+    /// The visibility is restored even if `f` panics. Like
+    /// [`set_menubar_visible`], hiding the menu bar might silently fail,
+    /// e.g. in full screen mode.
+    ///
+    /// [`set_menubar_visible`]: Self::set_menubar_visible
+    pub fn with_menubar_hidden<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct RestoreVisibility<'a> {
+            app: &'a InitializedApplication,
+            visible: bool,
+        }
 
-#[cfg(test)]
-mod tests {
-    use objc2::rc::autoreleasepool;
+        impl Drop for RestoreVisibility<'_> {
+            fn drop(&mut self) {
+                self.app.set_menubar_visible(self.visible);
+            }
+        }
 
-    use super::*;
+        let _guard = RestoreVisibility {
+            app: self,
+            visible: self.menubar_visible(),
+        };
+        self.set_menubar_visible(false);
+        f()
+    }
 
-    fn init_app() -> &'static InitializedApplication {
-        unimplemented!()
+    /// Whether the application is active, i.e. frontmost and receiving
+    /// keyboard events.
+    ///
+    /// Key equivalents are only matched against the menu bar while the
+    /// application is active, so inactive applications can't have their menu
+    /// actions triggered from the keyboard.
+    #[doc(alias = "isActive")]
+    pub fn is_active(&self) -> bool {
+        let active: Bool = unsafe { msg_send![self, isActive] };
+        active.is_true()
     }
 
-    fn create_menu() -> Id<NSMenu, Owned> {
-        unimplemented!()
+    /// Make the application active, e.g. before showing a context menu from
+    /// a status bar application that is running in the background.
+    ///
+    /// On macOS 14 and newer, this uses `activate`, which asks the system to
+    /// activate the application cooperatively, and `ignoring_other_apps` is
+    /// ignored; `activateIgnoringOtherApps:` is deprecated there, and its flag
+    /// no longer has an effect. On older systems, `ignoring_other_apps`
+    /// activates the application even if another application is currently
+    /// active, which should only be done in response to a user action.
+    #[doc(alias = "activateIgnoringOtherApps")]
+    #[doc(alias = "activateIgnoringOtherApps:")]
+    pub fn activate(&self, ignoring_other_apps: bool) {
+        let this = unsafe { &*(self as *const Self as *const Object) };
+        if availability::responds_to(this, sel!(activate)) {
+            unsafe { msg_send![self, activate] }
+        } else {
+            let ignoring_other_apps = Bool::new(ignoring_other_apps);
+            unsafe { msg_send![self, activateIgnoringOtherApps: ignoring_other_apps] }
+        }
     }
 
-    #[test]
-    #[ignore = "not implemented"]
-    fn test_services_menu() {
-        let app = init_app();
-        let menu1 = create_menu();
-        let menu2 = create_menu();
+    /// Run `f` with the new state whenever the application becomes active
+    /// or inactive, see [`is_active`].
+    ///
+    /// The callback is removed when the returned observer is dropped.
+    ///
+    /// [`is_active`]: Self::is_active
+    #[doc(alias = "NSApplicationDidBecomeActiveNotification")]
+    #[doc(alias = "NSApplicationDidResignActiveNotification")]
+    pub fn on_activation_change(&self, f: impl Fn(bool) + 'static) -> ActivationObserver {
+        ActivationObserver::new(f)
+    }
 
-        autoreleasepool(|pool| {
-            assert!(app.services_menu(pool).is_none());
+    /// Run `f` with the new visibility whenever the menu bar is shown or
+    /// hidden, e.g. when a window enters full screen and the menu bar
+    /// starts auto-hiding. Useful for repositioning overlay windows.
+    ///
+    /// This observes the application's `currentSystemPresentationOptions`
+    /// using key-value observing, and checks whether the options hide the
+    /// menu bar. Changes made with [`set_menubar_visible`] are reflected in
+    /// these options too.
+    ///
+    /// The callback is removed when the returned observer is dropped.
+    ///
+    /// [`set_menubar_visible`]: Self::set_menubar_visible
+    #[doc(alias = "currentSystemPresentationOptions")]
+    pub fn on_menubar_visibility_change(
+        &self,
+        f: impl Fn(bool) + 'static,
+    ) -> MenubarVisibilityObserver {
+        MenubarVisibilityObserver::new(f)
+    }
 
-            app.set_services_menu(&menu1);
-            assert_eq!(app.services_menu(pool).unwrap(), &*menu1);
+    /// The height of the menu bar in points, e.g. to lay out windows below
+    /// it.
+    ///
+    /// This can differ between displays, e.g. on displays with a camera
+    /// housing. Returns `0.0` if no menu bar has been set.
+    #[doc(alias = "menuBarHeight")]
+    pub fn menubar_height(&self) -> f64 {
+        // Only available on the global menu bar object
+        let menu: *const NSMenu = unsafe { msg_send![self, mainMenu] };
+        if menu.is_null() {
+            return 0.0;
+        }
+        let height: CGFloat = unsafe { msg_send![menu, menuBarHeight] };
+        height as f64
+    }
+}
 
-            app.set_services_menu(&menu2);
-            assert_eq!(app.services_menu(pool).unwrap(), &*menu2);
+static DOCK_MENU_KEY: u8 = 0;
+static SUPPRESSED_WINDOW_MENU_KEY: u8 = 0;
+static SUPPRESSED_HELP_MENU_KEY: u8 = 0;
+static DOCK_MENU_DELEGATE_KEY: u8 = 0;
 
-            // At this point `menu1` still shows as a services menu...
-        });
-    }
+extern "C" fn application_dock_menu(
+    _this: &Object,
+    _cmd: Sel,
+    _sender: *mut Object,
+) -> *mut NSMenu {
+    let app: *const Object = unsafe { msg_send![class!(NSApplication), sharedApplication] };
+    unsafe { associated::get(app, &DOCK_MENU_KEY) as *mut NSMenu }
+}
+
+fn dock_menu_delegate_class() -> &'static Class {
+    const CLASS_NAME: &str = "MenubarDockMenuDelegate";
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut builder = ClassBuilder::new(CLASS_NAME, class!(NSObject)).unwrap();
+        unsafe {
+            builder.add_method(
+                sel!(applicationDockMenu:),
+                application_dock_menu as extern "C" fn(&Object, Sel, *mut Object) -> *mut NSMenu,
+            );
+        }
+        builder.register();
+    });
+    Class::get(CLASS_NAME).unwrap()
 }
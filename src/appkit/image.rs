@@ -0,0 +1,133 @@
+use core::fmt;
+use objc2::rc::{Id, Shared};
+use objc2::{class, msg_send, sel};
+use objc2::{Encoding, Message, RefEncode};
+use objc2_foundation::{CGFloat, NSSize, NSString};
+
+use super::availability;
+
+/// A thin wrapper around an image, for use as menu item icons.
+#[repr(C)]
+pub struct NSImage {
+    _priv: [u8; 0],
+}
+
+unsafe impl RefEncode for NSImage {
+    const ENCODING_REF: Encoding<'static> = Encoding::Object;
+}
+
+unsafe impl Message for NSImage {}
+
+// Not `Sync`, since images can be resized through a shared reference
+unsafe impl Send for NSImage {}
+
+impl NSImage {
+    /// Create an image from an SF Symbol, e.g. `"folder"` or `"trash"`.
+    ///
+    /// Returns `None` if no symbol with the given name exists, or on macOS
+    /// versions older than 11, where symbol images are not available.
+    #[doc(alias = "imageWithSystemSymbolName")]
+    #[doc(alias = "imageWithSystemSymbolName:accessibilityDescription:")]
+    pub fn from_system_symbol(name: &str) -> Option<Id<Self, Shared>> {
+        if !availability::class_responds_to(
+            class!(NSImage),
+            sel!(imageWithSystemSymbolName:accessibilityDescription:),
+        ) {
+            return None;
+        }
+        let name = NSString::from_str(name);
+        let description: Option<&NSString> = None;
+        unsafe {
+            let ptr: *mut Self = msg_send![
+                class!(NSImage),
+                imageWithSystemSymbolName: &*name,
+                accessibilityDescription: description,
+            ];
+            Id::retain_autoreleased(ptr)
+        }
+    }
+
+    /// The size of the image in points, as `(width, height)`.
+    pub fn size(&self) -> (f64, f64) {
+        let size: NSSize = unsafe { msg_send![self, size] };
+        (size.width() as f64, size.height() as f64)
+    }
+
+    /// Change the size the image is drawn at, in points.
+    ///
+    /// Images are shared, so this affects every menu item (or other user)
+    /// of this image. Symbol images are created anew by each call to
+    /// [`from_system_symbol`], so they can be sized independently.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width or height is negative.
+    ///
+    /// [`from_system_symbol`]: Self::from_system_symbol
+    #[doc(alias = "setSize")]
+    #[doc(alias = "setSize:")]
+    pub fn set_size(&self, width: f64, height: f64) {
+        assert!(
+            width >= 0.0 && height >= 0.0,
+            "Failed setting image size: Size {}x{} is negative",
+            width,
+            height
+        );
+        let size = NSSize::new(width as CGFloat, height as CGFloat);
+        unsafe { msg_send![self, setSize: size] }
+    }
+}
+
+impl PartialEq for NSImage {
+    /// Pointer equality
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NSImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSImage")
+            .field("id", &(self as *const Self))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::rc::autoreleasepool;
+
+    #[test]
+    fn test_from_system_symbol() {
+        autoreleasepool(|_| {
+            assert!(NSImage::from_system_symbol("folder").is_some());
+            assert!(NSImage::from_system_symbol("not a real symbol name").is_none());
+        });
+    }
+
+    #[test]
+    fn test_size() {
+        autoreleasepool(|_| {
+            let image = NSImage::from_system_symbol("folder").unwrap();
+            let (width, height) = image.size();
+            assert!(width > 0.0 && height > 0.0);
+            image.set_size(32.0, 24.0);
+            assert_eq!(image.size(), (32.0, 24.0));
+
+            // Other instances of the symbol are unaffected
+            let other = NSImage::from_system_symbol("folder").unwrap();
+            assert_eq!(other.size(), (width, height));
+        });
+    }
+
+    #[test]
+    #[should_panic = "Failed setting image size: Size -1x16 is negative"]
+    fn test_negative_size() {
+        autoreleasepool(|_| {
+            let image = NSImage::from_system_symbol("folder").unwrap();
+            image.set_size(-1.0, 16.0);
+        });
+    }
+}
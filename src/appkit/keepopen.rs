@@ -0,0 +1,178 @@
+//! A view for menu items that don't close the menu when clicked.
+//!
+//! AppKit always closes the menu before sending the action of a regular
+//! item. Clicks on an item's custom view are instead delivered to the view,
+//! and the menu stays open. So the view here draws the item like AppKit
+//! would (roughly), and sends the action itself when clicked.
+use std::sync::Once;
+
+use objc2::declare::ClassBuilder;
+use objc2::ffi::{NSInteger, NSUInteger};
+use objc2::rc::{Id, Owned};
+use objc2::runtime::{Bool, Class, Object, Sel};
+use objc2::{class, msg_send, sel};
+use objc2_foundation::{CGFloat, NSPoint, NSRect, NSSize, NSString};
+
+use super::attributed::{NSFontAttributeName, NSForegroundColorAttributeName};
+use super::availability;
+use super::view::NSView;
+
+const CLASS_NAME: &str = "MenubarKeepOpenView";
+
+/// The height of a regular menu item.
+const HEIGHT: CGFloat = 22.0;
+/// Where the title starts, leaving room for the state column.
+const TITLE_X: CGFloat = 21.0;
+/// Space after the title.
+const TRAILING: CGFloat = 20.0;
+/// Inset of the selection highlight from the edges of the menu.
+const HIGHLIGHT_INSET: CGFloat = 5.0;
+
+/// `NSViewWidthSizable`
+const WIDTH_SIZABLE: NSUInteger = 1 << 1;
+/// `NSControlStateValueOn`
+const STATE_ON: NSInteger = 1;
+
+fn class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut builder = ClassBuilder::new(CLASS_NAME, class!(NSView)).unwrap();
+        unsafe {
+            builder.add_method(
+                sel!(drawRect:),
+                draw_rect as extern "C" fn(&Object, Sel, NSRect),
+            );
+            builder.add_method(
+                sel!(mouseUp:),
+                mouse_up as extern "C" fn(&Object, Sel, *mut Object),
+            );
+        }
+        builder.register();
+    });
+    Class::get(CLASS_NAME).unwrap()
+}
+
+fn menu_font() -> *mut Object {
+    // A size of zero means the default size
+    unsafe { msg_send![class!(NSFont), menuFontOfSize: 0.0 as CGFloat] }
+}
+
+/// The drawing attributes for a title in the given color.
+fn attributes(color: *mut Object) -> Id<Object, Owned> {
+    unsafe {
+        let ptr: *mut Object = msg_send![class!(NSMutableDictionary), alloc];
+        let attributes: Id<Object, Owned> = Id::new(msg_send![ptr, init]).unwrap();
+        let _: () = msg_send![&*attributes, setObject: menu_font(), forKey: NSFontAttributeName];
+        let _: () =
+            msg_send![&*attributes, setObject: color, forKey: NSForegroundColorAttributeName];
+        attributes
+    }
+}
+
+fn color(name: Sel) -> *mut Object {
+    unsafe { msg_send![class!(NSColor), performSelector: name] }
+}
+
+extern "C" fn draw_rect(this: &Object, _cmd: Sel, _dirty: NSRect) {
+    let item: *mut Object = unsafe { msg_send![this, enclosingMenuItem] };
+    let item = match unsafe { item.as_ref() } {
+        Some(item) => item,
+        None => return,
+    };
+    let bounds: NSRect = unsafe { msg_send![this, bounds] };
+    let enabled: Bool = unsafe { msg_send![item, isEnabled] };
+    let highlighted: Bool = unsafe { msg_send![item, isHighlighted] };
+    let highlighted = enabled.is_true() && highlighted.is_true();
+
+    if highlighted {
+        let background = if availability::class_responds_to(
+            class!(NSColor),
+            sel!(selectedContentBackgroundColor),
+        ) {
+            color(sel!(selectedContentBackgroundColor))
+        } else {
+            color(sel!(selectedMenuItemColor))
+        };
+        let inset = NSRect::new(
+            NSPoint::new(bounds.origin.x + HIGHLIGHT_INSET, bounds.origin.y),
+            NSSize::new(
+                (bounds.size.width() - 2.0 * HIGHLIGHT_INSET).max(0.0),
+                bounds.size.height(),
+            ),
+        );
+        unsafe {
+            let _: () = msg_send![background, set];
+            let path: *mut Object = msg_send![
+                class!(NSBezierPath),
+                bezierPathWithRoundedRect: inset,
+                xRadius: 4.0 as CGFloat,
+                yRadius: 4.0 as CGFloat,
+            ];
+            let _: () = msg_send![path, fill];
+        }
+    }
+
+    let text_color = if highlighted {
+        color(sel!(selectedMenuItemTextColor))
+    } else if enabled.is_true() {
+        color(sel!(controlTextColor))
+    } else {
+        color(sel!(disabledControlTextColor))
+    };
+    let attributes = attributes(text_color);
+    let draw = |text: &NSString, x: CGFloat| unsafe {
+        let size: NSSize = msg_send![text, sizeWithAttributes: &*attributes];
+        let y = bounds.origin.y + (bounds.size.height() - size.height()) / 2.0;
+        let _: () = msg_send![text, drawAtPoint: NSPoint::new(x, y), withAttributes: &*attributes];
+    };
+
+    let state: NSInteger = unsafe { msg_send![item, state] };
+    if state == STATE_ON {
+        draw(&NSString::from_str("✓"), bounds.origin.x + 8.0);
+    }
+    let title: &NSString = unsafe { msg_send![item, title] };
+    draw(title, bounds.origin.x + TITLE_X);
+}
+
+extern "C" fn mouse_up(this: &Object, _cmd: Sel, _event: *mut Object) {
+    let item: *mut Object = unsafe { msg_send![this, enclosingMenuItem] };
+    if item.is_null() {
+        return;
+    }
+    let enabled: Bool = unsafe { msg_send![item, isEnabled] };
+    if enabled.is_true() {
+        unsafe {
+            let action: Sel = msg_send![item, action];
+            let target: *mut Object = msg_send![item, target];
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            if !action.as_ptr().is_null() {
+                let _: Bool = msg_send![app, sendAction: action, to: target, from: item];
+            }
+        }
+    }
+    // The action may have changed e.g. the state or title
+    let _: () = unsafe { msg_send![this, setNeedsDisplay: Bool::YES] };
+}
+
+/// Create a view that draws `title` like a menu item.
+pub(super) fn new(title: &NSString) -> Id<NSView, Owned> {
+    let attributes = attributes(color(sel!(controlTextColor)));
+    let size: NSSize = unsafe { msg_send![title, sizeWithAttributes: &*attributes] };
+    let frame = NSRect::new(
+        NSPoint::new(0.0, 0.0),
+        NSSize::new(TITLE_X + size.width() + TRAILING, HEIGHT),
+    );
+    unsafe {
+        let ptr: *mut Object = msg_send![class(), alloc];
+        let view: Id<NSView, Owned> = Id::new(msg_send![ptr, initWithFrame: frame]).unwrap();
+        // Fill the width of the menu
+        let _: () = msg_send![&*view, setAutoresizingMask: WIDTH_SIZABLE];
+        view
+    }
+}
+
+/// Whether `view` was created with [`new`].
+pub(super) fn is_keep_open_view(view: &NSView) -> bool {
+    let is_kind: Bool = unsafe { msg_send![view, isKindOfClass: class()] };
+    is_kind.is_true()
+}
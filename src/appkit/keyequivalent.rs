@@ -0,0 +1,458 @@
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use std::error::Error;
+
+use bitflags::bitflags;
+use objc2::ffi::NSUInteger;
+
+bitflags! {
+    /// The modifier keys that must be held for a key equivalent to trigger.
+    ///
+    /// Defaults to [`KeyModifiers::COMMAND`] on newly created menu items.
+    #[doc(alias = "NSEventModifierFlags")]
+    pub struct KeyModifiers: NSUInteger {
+        #[doc(alias = "NSEventModifierFlagShift")]
+        const SHIFT = 1 << 17;
+        #[doc(alias = "NSEventModifierFlagControl")]
+        const CONTROL = 1 << 18;
+        #[doc(alias = "NSEventModifierFlagOption")]
+        const OPTION = 1 << 19;
+        #[doc(alias = "NSEventModifierFlagCommand")]
+        const COMMAND = 1 << 20;
+        #[doc(alias = "NSEventModifierFlagFunction")]
+        const FUNCTION = 1 << 23;
+    }
+}
+
+impl KeyModifiers {
+    /// Parse modifiers from a string like `"cmd+shift"`, e.g. from a
+    /// configuration file.
+    ///
+    /// Modifiers are separated by `+`, are case-insensitive, and can be
+    /// written as:
+    /// - `cmd`, `command` or `meta` for [`KeyModifiers::COMMAND`].
+    /// - `shift` for [`KeyModifiers::SHIFT`].
+    /// - `opt`, `option` or `alt` for [`KeyModifiers::OPTION`].
+    /// - `ctrl` or `control` for [`KeyModifiers::CONTROL`].
+    /// - `fn` for [`KeyModifiers::FUNCTION`].
+    ///
+    /// An empty string means no modifiers. The key itself is not part of
+    /// this; for a full shortcut like `"cmd+shift+n"`, split off the key
+    /// first, e.g. with [`str::rsplit_once`].
+    ///
+    /// ```
+    /// use menubar::appkit::KeyModifiers;
+    ///
+    /// let modifiers = KeyModifiers::parse("cmd+shift").unwrap();
+    /// assert_eq!(modifiers, KeyModifiers::COMMAND | KeyModifiers::SHIFT);
+    /// assert!(KeyModifiers::parse("cmd+hyper").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, ParseModifiersError> {
+        let mut modifiers = Self::empty();
+        if s.trim().is_empty() {
+            return Ok(modifiers);
+        }
+        for token in s.split('+') {
+            let token = token.trim();
+            modifiers |= match &*token.to_lowercase() {
+                "cmd" | "command" | "meta" => Self::COMMAND,
+                "shift" => Self::SHIFT,
+                "opt" | "option" | "alt" => Self::OPTION,
+                "ctrl" | "control" => Self::CONTROL,
+                "fn" => Self::FUNCTION,
+                _ => {
+                    return Err(ParseModifiersError {
+                        token: token.to_owned(),
+                    })
+                }
+            };
+        }
+        Ok(modifiers)
+    }
+}
+
+impl FromStr for KeyModifiers {
+    type Err = ParseModifiersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The error returned by [`KeyModifiers::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseModifiersError {
+    token: String,
+}
+
+impl ParseModifiersError {
+    /// The part of the string that is not a modifier.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl fmt::Display for ParseModifiersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown key modifier {:?}, expected one of cmd, shift, option, control or fn",
+            self.token
+        )
+    }
+}
+
+impl Error for ParseModifiersError {}
+
+/// A special key, for use as a key equivalent.
+///
+/// AppKit represents keys without a printable character (function keys,
+/// arrows and so on) as characters in Unicode's private use area, e.g.
+/// `'\u{F704}'` for F1. This maps each key to the right string, which can be
+/// passed to [`NSMenuItem::set_key_equivalent`]:
+///
+/// ```no_run
+/// use menubar::appkit::{KeyEquivalent, KeyModifiers, NSMenuItem};
+/// use std::convert::TryFrom;
+///
+/// let key = String::try_from(KeyEquivalent::F(1)).unwrap();
+/// let mut item = NSMenuItem::new("Help", "", None);
+/// item.set_key_equivalent(&key, KeyModifiers::empty());
+/// ```
+///
+/// [`NSMenuItem::set_key_equivalent`]: super::NSMenuItem::set_key_equivalent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyEquivalent {
+    /// A regular character key, e.g. `'n'`.
+    Char(char),
+    /// A function key, from F1 to F35.
+    ///
+    /// Converting a number outside of that range fails with an
+    /// [`InvalidFunctionKeyError`].
+    #[doc(alias = "NSF1FunctionKey")]
+    F(u8),
+    Escape,
+    Return,
+    /// The return key on the numeric keypad.
+    Enter,
+    Tab,
+    /// The key labelled "delete", which deletes backwards.
+    Backspace,
+    /// Forward delete.
+    #[doc(alias = "NSDeleteFunctionKey")]
+    Delete,
+    Space,
+    #[doc(alias = "NSUpArrowFunctionKey")]
+    Up,
+    #[doc(alias = "NSDownArrowFunctionKey")]
+    Down,
+    #[doc(alias = "NSLeftArrowFunctionKey")]
+    Left,
+    #[doc(alias = "NSRightArrowFunctionKey")]
+    Right,
+    #[doc(alias = "NSHomeFunctionKey")]
+    Home,
+    #[doc(alias = "NSEndFunctionKey")]
+    End,
+    #[doc(alias = "NSPageUpFunctionKey")]
+    PageUp,
+    #[doc(alias = "NSPageDownFunctionKey")]
+    PageDown,
+}
+
+impl TryFrom<KeyEquivalent> for char {
+    type Error = InvalidFunctionKeyError;
+
+    fn try_from(key: KeyEquivalent) -> Result<Self, Self::Error> {
+        Ok(match key {
+            KeyEquivalent::Char(c) => c,
+            KeyEquivalent::F(n) => {
+                if !(1..=35).contains(&n) {
+                    return Err(InvalidFunctionKeyError { number: n });
+                }
+                char::from_u32(F1 + u32::from(n) - 1).unwrap()
+            }
+            KeyEquivalent::Escape => '\u{1b}',
+            KeyEquivalent::Return => '\r',
+            KeyEquivalent::Enter => '\u{3}',
+            KeyEquivalent::Tab => '\t',
+            KeyEquivalent::Backspace => '\u{8}',
+            KeyEquivalent::Delete => '\u{F728}',
+            KeyEquivalent::Space => ' ',
+            KeyEquivalent::Up => '\u{F700}',
+            KeyEquivalent::Down => '\u{F701}',
+            KeyEquivalent::Left => '\u{F702}',
+            KeyEquivalent::Right => '\u{F703}',
+            KeyEquivalent::Home => '\u{F729}',
+            KeyEquivalent::End => '\u{F72B}',
+            KeyEquivalent::PageUp => '\u{F72C}',
+            KeyEquivalent::PageDown => '\u{F72D}',
+        })
+    }
+}
+
+impl TryFrom<KeyEquivalent> for String {
+    type Error = InvalidFunctionKeyError;
+
+    fn try_from(key: KeyEquivalent) -> Result<Self, Self::Error> {
+        char::try_from(key).map(|c| c.to_string())
+    }
+}
+
+/// The error returned when converting a [`KeyEquivalent::F`] outside of F1
+/// to F35.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidFunctionKeyError {
+    number: u8,
+}
+
+impl InvalidFunctionKeyError {
+    /// The number of the function key, e.g. `0` for `F(0)`.
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+}
+
+impl fmt::Display for InvalidFunctionKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid function key F{}, expected F1 to F35",
+            self.number
+        )
+    }
+}
+
+impl Error for InvalidFunctionKeyError {}
+
+// Function keys, see `NSF1FunctionKey` and friends
+const F1: u32 = 0xF704;
+const F35: u32 = 0xF726;
+
+/// Format a key equivalent the way AppKit draws it in menus, e.g. `"⇧⌘N"`.
+///
+/// Modifiers are shown in the conventional order (Control, Option, Shift,
+/// Command), followed by the key. Letters are shown in uppercase, and an
+/// uppercase letter implies Shift, like in [`NSMenuItem::set_key_equivalent`].
+/// Special keys like arrows, return and delete are shown using their usual
+/// glyphs.
+///
+/// Useful for showing shortcuts elsewhere in the UI, e.g. in tooltips.
+///
+/// [`NSMenuItem::set_key_equivalent`]: super::NSMenuItem::set_key_equivalent
+pub fn format_key_equivalent(key: &str, modifiers: KeyModifiers) -> String {
+    let (key, modifiers) = normalize_key_equivalent(key, modifiers);
+    let mut chars = key.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) => format_key(c),
+        _ => key.to_uppercase(),
+    };
+
+    let mut result = String::new();
+    if modifiers.contains(KeyModifiers::FUNCTION) {
+        result.push_str("fn");
+    }
+    for &(modifier, glyph) in &[
+        (KeyModifiers::CONTROL, '⌃'),
+        (KeyModifiers::OPTION, '⌥'),
+        (KeyModifiers::SHIFT, '⇧'),
+        (KeyModifiers::COMMAND, '⌘'),
+    ] {
+        if modifiers.contains(modifier) {
+            result.push(glyph);
+        }
+    }
+    result.push_str(&key);
+    result
+}
+
+/// Turn a single uppercase key into the lowercase key with
+/// [`KeyModifiers::SHIFT`], which is how AppKit interprets it.
+///
+/// Other keys are returned as is.
+pub(super) fn normalize_key_equivalent(
+    key: &str,
+    mut modifiers: KeyModifiers,
+) -> (String, KeyModifiers) {
+    let mut chars = key.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_uppercase() => {
+            modifiers |= KeyModifiers::SHIFT;
+            c.to_lowercase().collect()
+        }
+        _ => key.to_owned(),
+    };
+    (key, modifiers)
+}
+
+fn format_key(key: char) -> String {
+    let glyph = match key {
+        '\r' => '↩',
+        '\u{3}' => '⌤',
+        '\t' => '⇥',
+        '\u{19}' => '⇤',
+        '\u{1b}' => '⎋',
+        '\u{8}' | '\u{7f}' => '⌫',
+        ' ' => return "Space".to_owned(),
+        // NSUpArrowFunctionKey, NSDownArrowFunctionKey, ...
+        '\u{F700}' => '↑',
+        '\u{F701}' => '↓',
+        '\u{F702}' => '←',
+        '\u{F703}' => '→',
+        // NSDeleteFunctionKey
+        '\u{F728}' => '⌦',
+        // NSHomeFunctionKey, NSEndFunctionKey, NSPageUpFunctionKey, NSPageDownFunctionKey
+        '\u{F729}' => '↖',
+        '\u{F72B}' => '↘',
+        '\u{F72C}' => '⇞',
+        '\u{F72D}' => '⇟',
+        c if (F1..=F35).contains(&(c as u32)) => {
+            return format!("F{}", c as u32 - F1 + 1);
+        }
+        c => return c.to_uppercase().collect(),
+    };
+    glyph.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_modifiers() {
+        assert_eq!(format_key_equivalent("n", KeyModifiers::COMMAND), "⌘N");
+        assert_eq!(format_key_equivalent("N", KeyModifiers::COMMAND), "⇧⌘N");
+        assert_eq!(
+            format_key_equivalent("n", KeyModifiers::COMMAND | KeyModifiers::SHIFT),
+            "⇧⌘N"
+        );
+        assert_eq!(format_key_equivalent("n", KeyModifiers::all()), "fn⌃⌥⇧⌘N");
+        assert_eq!(format_key_equivalent("n", KeyModifiers::empty()), "N");
+        assert_eq!(format_key_equivalent(",", KeyModifiers::COMMAND), "⌘,");
+    }
+
+    #[test]
+    fn test_format_special_keys() {
+        let cmd = KeyModifiers::COMMAND;
+        assert_eq!(format_key_equivalent("\r", cmd), "⌘↩");
+        assert_eq!(format_key_equivalent("\u{8}", cmd), "⌘⌫");
+        assert_eq!(format_key_equivalent("\u{7f}", cmd), "⌘⌫");
+        assert_eq!(format_key_equivalent("\u{F728}", cmd), "⌘⌦");
+        assert_eq!(format_key_equivalent("\u{1b}", cmd), "⌘⎋");
+        assert_eq!(format_key_equivalent("\t", cmd), "⌘⇥");
+        assert_eq!(format_key_equivalent(" ", cmd), "⌘Space");
+        assert_eq!(format_key_equivalent("\u{F700}", cmd), "⌘↑");
+        assert_eq!(format_key_equivalent("\u{F701}", cmd), "⌘↓");
+        assert_eq!(format_key_equivalent("\u{F702}", cmd), "⌘←");
+        assert_eq!(format_key_equivalent("\u{F703}", cmd), "⌘→");
+        assert_eq!(format_key_equivalent("\u{F704}", cmd), "⌘F1");
+        assert_eq!(format_key_equivalent("\u{F70F}", cmd), "⌘F12");
+        assert_eq!(format_key_equivalent("\u{F726}", cmd), "⌘F35");
+    }
+
+    #[test]
+    fn test_normalize_key_equivalent() {
+        assert_eq!(
+            normalize_key_equivalent("N", KeyModifiers::COMMAND),
+            ("n".to_owned(), KeyModifiers::COMMAND | KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            normalize_key_equivalent("n", KeyModifiers::COMMAND),
+            ("n".to_owned(), KeyModifiers::COMMAND)
+        );
+        assert_eq!(
+            normalize_key_equivalent("NO", KeyModifiers::COMMAND),
+            ("NO".to_owned(), KeyModifiers::COMMAND)
+        );
+        assert_eq!(
+            normalize_key_equivalent("", KeyModifiers::empty()),
+            (String::new(), KeyModifiers::empty())
+        );
+    }
+
+    #[test]
+    fn test_parse_modifiers() {
+        let parse = |s| KeyModifiers::parse(s).unwrap();
+        assert_eq!(parse(""), KeyModifiers::empty());
+        assert_eq!(parse("  "), KeyModifiers::empty());
+        for s in &["cmd", "command", "meta", "CMD", "Command"] {
+            assert_eq!(parse(s), KeyModifiers::COMMAND);
+        }
+        assert_eq!(parse("shift"), KeyModifiers::SHIFT);
+        for s in &["opt", "option", "alt", "Alt"] {
+            assert_eq!(parse(s), KeyModifiers::OPTION);
+        }
+        for s in &["ctrl", "control"] {
+            assert_eq!(parse(s), KeyModifiers::CONTROL);
+        }
+        assert_eq!(parse("fn"), KeyModifiers::FUNCTION);
+        assert_eq!(
+            parse("cmd+shift"),
+            KeyModifiers::COMMAND | KeyModifiers::SHIFT
+        );
+        assert_eq!(
+            parse(" ctrl + alt + cmd "),
+            KeyModifiers::CONTROL | KeyModifiers::OPTION | KeyModifiers::COMMAND
+        );
+        assert_eq!(parse("cmd+cmd"), KeyModifiers::COMMAND);
+        assert_eq!(parse("fn+ctrl+opt+shift+cmd"), KeyModifiers::all());
+        assert_eq!(
+            "cmd+opt".parse(),
+            Ok(KeyModifiers::COMMAND | KeyModifiers::OPTION)
+        );
+    }
+
+    #[test]
+    fn test_parse_modifiers_error() {
+        let error = KeyModifiers::parse("cmd+shift+n").unwrap_err();
+        assert_eq!(error.token(), "n");
+        assert_eq!(
+            error.to_string(),
+            "unknown key modifier \"n\", expected one of cmd, shift, option, control or fn"
+        );
+        assert_eq!(KeyModifiers::parse("cmd++shift").unwrap_err().token(), "");
+        assert_eq!(KeyModifiers::parse("hyper").unwrap_err().token(), "hyper");
+    }
+
+    #[test]
+    fn test_key_equivalent_string() {
+        let string = |key| String::try_from(key).unwrap();
+        assert_eq!(string(KeyEquivalent::Char('n')), "n");
+        assert_eq!(string(KeyEquivalent::F(1)), "\u{F704}");
+        assert_eq!(string(KeyEquivalent::F(12)), "\u{F70F}");
+        assert_eq!(string(KeyEquivalent::F(35)), "\u{F726}");
+        assert_eq!(string(KeyEquivalent::Escape), "\u{1b}");
+
+        let cmd = KeyModifiers::COMMAND;
+        let format = |key| format_key_equivalent(&string(key), cmd);
+        assert_eq!(format(KeyEquivalent::F(5)), "⌘F5");
+        assert_eq!(format(KeyEquivalent::Escape), "⌘⎋");
+        assert_eq!(format(KeyEquivalent::Return), "⌘↩");
+        assert_eq!(format(KeyEquivalent::Enter), "⌘⌤");
+        assert_eq!(format(KeyEquivalent::Backspace), "⌘⌫");
+        assert_eq!(format(KeyEquivalent::Delete), "⌘⌦");
+        assert_eq!(format(KeyEquivalent::Space), "⌘Space");
+        assert_eq!(format(KeyEquivalent::Left), "⌘←");
+        assert_eq!(format(KeyEquivalent::PageDown), "⌘⇟");
+    }
+
+    #[test]
+    fn test_key_equivalent_invalid_function_key() {
+        let error = char::try_from(KeyEquivalent::F(0)).unwrap_err();
+        assert_eq!(error.number(), 0);
+        assert_eq!(
+            error.to_string(),
+            "invalid function key F0, expected F1 to F35"
+        );
+        assert_eq!(
+            String::try_from(KeyEquivalent::F(36)),
+            Err(InvalidFunctionKeyError { number: 36 })
+        );
+    }
+
+    #[test]
+    fn test_format_empty() {
+        assert_eq!(format_key_equivalent("", KeyModifiers::COMMAND), "⌘");
+    }
+}
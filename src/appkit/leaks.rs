@@ -0,0 +1,139 @@
+//! Diagnostics for finding menus and menu items that are never released.
+//!
+//! Objects are only tracked in debug builds with the `leak-checks` feature
+//! enabled; otherwise this compiles to nothing.
+use objc2::runtime::Object;
+use objc2::Message;
+
+#[cfg(all(feature = "leak-checks", debug_assertions))]
+mod imp {
+    use core::cell::RefCell;
+
+    use objc2::ffi;
+    use objc2::msg_send;
+    use objc2::rc::autoreleasepool;
+    use objc2::runtime::Object;
+    use objc2_foundation::NSString;
+
+    /// A zeroing weak reference, which the runtime clears when the object
+    /// is deallocated.
+    ///
+    /// Boxed, since the runtime tracks the address of the reference.
+    struct Weak(Box<*mut ffi::objc_object>);
+
+    impl Weak {
+        fn new(obj: &Object) -> Self {
+            let mut weak = Box::new(core::ptr::null_mut());
+            let obj = obj as *const Object as *mut ffi::objc_object;
+            unsafe { ffi::objc_initWeak(&mut *weak, obj) };
+            Self(weak)
+        }
+
+        /// Call `f` with the object if it is still alive.
+        fn with<R>(&mut self, f: impl FnOnce(&Object) -> R) -> Option<R> {
+            let obj = unsafe { ffi::objc_loadWeakRetained(&mut *self.0) };
+            let result = unsafe { obj.cast::<Object>().as_ref() }.map(f);
+            unsafe { ffi::objc_release(obj) };
+            result
+        }
+
+        fn is_alive(&mut self) -> bool {
+            self.with(|_| ()).is_some()
+        }
+    }
+
+    impl Drop for Weak {
+        fn drop(&mut self) {
+            unsafe { ffi::objc_destroyWeak(&mut *self.0) };
+        }
+    }
+
+    thread_local! {
+        /// Menus are only used from the thread they were created on (usually
+        /// the main thread), so there's no need to synchronize this.
+        static TRACKED: RefCell<Vec<(&'static str, Weak)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub(super) fn track(obj: &Object, kind: &'static str) {
+        TRACKED.with(|tracked| {
+            let mut tracked = tracked.borrow_mut();
+            // Forget released objects before growing, so that the list
+            // stays proportional to the number of live objects
+            if tracked.len() == tracked.capacity() {
+                tracked.retain_mut(|(_, weak)| weak.is_alive());
+            }
+            tracked.push((kind, Weak::new(obj)));
+        });
+    }
+
+    pub(super) fn report() -> usize {
+        TRACKED.with(|tracked| {
+            let mut tracked = tracked.borrow_mut();
+            tracked.retain_mut(|(kind, weak)| {
+                let kind = *kind;
+                weak.with(|obj| {
+                    let title = autoreleasepool(|pool| {
+                        let title: *const NSString = unsafe { msg_send![obj, title] };
+                        unsafe { title.as_ref() }
+                            .map(|title| title.as_str(pool).to_owned())
+                            .unwrap_or_default()
+                    });
+                    eprintln!(
+                        "menubar: {} {:?} with title {:?} is still alive",
+                        kind, obj as *const Object, title
+                    );
+                })
+                .is_some()
+            });
+            tracked.len()
+        })
+    }
+}
+
+/// Start tracking a newly created object, see [`report_leaks`].
+#[inline]
+pub(super) fn track<T: Message>(obj: &T, kind: &'static str) {
+    #[cfg(all(feature = "leak-checks", debug_assertions))]
+    imp::track(unsafe { &*(obj as *const T as *const Object) }, kind);
+}
+
+/// Print the menus and menu items created by this crate on the current
+/// thread that are still alive to stderr, and return how many there are.
+///
+/// Call this at a point where all menus are expected to have been released,
+/// e.g. after closing a window with a context menu, to catch objects that
+/// are kept alive by accident.
+///
+/// Objects are only tracked in debug builds, so this always returns `0` in
+/// release builds.
+#[cfg(feature = "leak-checks")]
+pub fn report_leaks() -> usize {
+    #[cfg(debug_assertions)]
+    return imp::report();
+    #[cfg(not(debug_assertions))]
+    return 0;
+}
+
+#[cfg(all(test, feature = "leak-checks", debug_assertions))]
+mod tests {
+    use super::*;
+    use crate::appkit::{NSMenu, NSMenuItem};
+    use objc2::rc::autoreleasepool;
+
+    #[test]
+    fn test_report_leaks() {
+        // Run on a fresh thread, so that only the objects created here are
+        // tracked
+        std::thread::spawn(|| {
+            autoreleasepool(|_| {
+                let mut menu = NSMenu::new();
+                menu.add(NSMenuItem::new("item", "", None));
+                assert_eq!(report_leaks(), 2);
+                drop(menu);
+            });
+            assert_eq!(report_leaks(), 0);
+        })
+        .join()
+        .unwrap();
+    }
+}
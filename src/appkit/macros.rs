@@ -0,0 +1,148 @@
+/// Declaratively build a [`MenuBar`].
+///
+/// Each top-level entry is a title followed by the items of that menu. The
+/// first entry becomes the application menu, whose title is ignored by
+/// AppKit. Items are one of:
+///
+/// - `item(title)`, optionally followed by `key = "n"` (a key equivalent
+///   using the Command modifier) and/or `action = || ...`.
+/// - `separator()`.
+/// - `submenu(title) => { ... }`, containing further items.
+///
+/// The macro only expands to the regular builder calls, so it's entirely
+/// optional.
+///
+/// # Example
+///
+/// ```no_run
+/// use menubar::menu;
+///
+/// let menubar = menu! {
+///     "App" => {
+///         item("Quit", key = "q", action = || std::process::exit(0)),
+///     },
+///     "File" => {
+///         item("New", key = "n"),
+///         separator(),
+///         submenu("Recent") => {
+///             item("Clear Menu"),
+///         },
+///     },
+/// };
+/// ```
+///
+/// [`MenuBar`]: crate::appkit::MenuBar
+#[macro_export]
+macro_rules! menu {
+    (@items $menu:ident;) => {};
+    (@items $menu:ident; separator() $(, $($rest:tt)*)?) => {
+        let _ = $menu.add($crate::appkit::NSMenuItem::new_separator());
+        $crate::menu!(@items $menu; $($($rest)*)?);
+    };
+    (@items $menu:ident;
+        item($title:expr $(, key = $key:expr)? $(, action = $action:expr)? $(,)?)
+        $(, $($rest:tt)*)?
+    ) => {
+        #[allow(unused_mut)]
+        let mut item = $crate::appkit::NSMenuItem::new($title, "", None);
+        $(item.set_key_equivalent($key, $crate::appkit::KeyModifiers::COMMAND);)?
+        $(item.set_action($action);)?
+        let _ = $menu.add(item);
+        $crate::menu!(@items $menu; $($($rest)*)?);
+    };
+    (@items $menu:ident;
+        submenu($title:expr) => { $($items:tt)* }
+        $(, $($rest:tt)*)?
+    ) => {
+        let title = $title;
+        let mut item = $crate::appkit::NSMenuItem::new(title, "", None);
+        let mut submenu = $crate::appkit::NSMenu::new();
+        submenu.set_title(title);
+        {
+            let submenu = &mut *submenu;
+            $crate::menu!(@items submenu; $($items)*);
+        }
+        let _ = item.set_submenu(Some(submenu));
+        let _ = $menu.add(item);
+        $crate::menu!(@items $menu; $($($rest)*)?);
+    };
+    (
+        $first_title:expr => { $($first:tt)* }
+        $(, $title:expr => { $($items:tt)* })*
+        $(,)?
+    ) => {{
+        let _ = $first_title;
+        #[allow(unused_mut)]
+        let mut menubar = $crate::appkit::MenuBar::new(|menu| {
+            $crate::menu!(@items menu; $($first)*);
+        });
+        $(
+            let _ = menubar.add($title, |menu| {
+                $crate::menu!(@items menu; $($items)*);
+            });
+        )*
+        menubar
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use std::rc::Rc;
+
+    use objc2::rc::autoreleasepool;
+
+    use crate::appkit::NSMenu;
+
+    #[test]
+    fn test_menu() {
+        let called = Rc::new(Cell::new(false));
+        let called_clone = called.clone();
+        let menubar = menu! {
+            "App" => {
+                item("Quit", key = "q", action = move || called_clone.set(true)),
+            },
+            "File" => {
+                item("New", key = "n"),
+                separator(),
+                submenu("Recent") => {
+                    item("Clear Menu"),
+                },
+            },
+        };
+        assert_eq!(menubar.title(1), "File");
+
+        let root = menubar.into_raw();
+        autoreleasepool(|pool| {
+            let app = root.item_at(pool, 0).unwrap().submenu(pool).unwrap();
+            let quit = app.item_at(pool, 0).unwrap();
+            assert_eq!(quit.title(pool), "Quit");
+            assert_eq!(quit.key_equivalent(pool), "q");
+            assert!(!called.get());
+
+            let file: &NSMenu = root.item_at(pool, 1).unwrap().submenu(pool).unwrap();
+            assert_eq!(file.len(), 3);
+            assert_eq!(file.item_at(pool, 0).unwrap().title(pool), "New");
+            assert!(file.item_at(pool, 1).unwrap().is_separator());
+            let recent = file.item_at(pool, 2).unwrap().submenu(pool).unwrap();
+            assert_eq!(recent.title(), "Recent");
+            assert_eq!(recent.item_at(pool, 0).unwrap().title(pool), "Clear Menu");
+        });
+    }
+
+    #[test]
+    fn test_submenu_title_evaluated_once() {
+        let count = Cell::new(0);
+        let title = || {
+            count.set(count.get() + 1);
+            "Recent"
+        };
+        let _menubar = menu! {
+            "App" => {},
+            "File" => {
+                submenu(title()) => {},
+            },
+        };
+        assert_eq!(count.get(), 1);
+    }
+}
@@ -0,0 +1,74 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use objc2::runtime::Bool;
+use objc2::{class, msg_send};
+
+/// Proof that the current thread is the main thread.
+///
+/// AppKit expects the menu bar and the application object to only be used
+/// from the main thread. [`InitializedApplication::new`] and
+/// [`NSStatusBar::system`] require this marker, so the application and the
+/// status bar can only be obtained on the main thread, and since the
+/// returned references can't be sent to other threads, they are only used
+/// there. Attaching menus to the live application, e.g. with
+/// [`InitializedApplication::set_menubar`], in turn goes through the
+/// application.
+///
+/// Creating menus does not require the marker: [`NSMenu`] and
+/// [`NSMenuItem`] can be created and used on any thread (though they can't
+/// be sent between threads), so that menus can be built and tested before
+/// they are attached.
+///
+/// [`InitializedApplication::new`]: super::InitializedApplication::new
+/// [`InitializedApplication::set_menubar`]: super::InitializedApplication::set_menubar
+/// [`NSStatusBar::system`]: super::NSStatusBar::system
+/// [`NSMenu`]: super::NSMenu
+/// [`NSMenuItem`]: super::NSMenuItem
+#[derive(Clone, Copy)]
+pub struct MainThreadMarker {
+    /// Makes the marker `!Send` and `!Sync`
+    _priv: PhantomData<*mut ()>,
+}
+
+impl MainThreadMarker {
+    /// Get the marker, or `None` if this is not the main thread.
+    #[doc(alias = "isMainThread")]
+    pub fn new() -> Option<Self> {
+        let is_main: Bool = unsafe { msg_send![class!(NSThread), isMainThread] };
+        if is_main.is_true() {
+            // SAFETY: Just checked
+            Some(unsafe { Self::new_unchecked() })
+        } else {
+            None
+        }
+    }
+
+    /// Get the marker without checking the current thread.
+    ///
+    /// # Safety
+    ///
+    /// The current thread must be the main thread.
+    pub unsafe fn new_unchecked() -> Self {
+        Self { _priv: PhantomData }
+    }
+}
+
+impl fmt::Debug for MainThreadMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MainThreadMarker").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_main_thread() {
+        // Tests are run on other threads than the main thread
+        std::thread::spawn(|| assert!(MainThreadMarker::new().is_none()))
+            .join()
+            .unwrap();
+    }
+}
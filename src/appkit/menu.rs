@@ -1,26 +1,56 @@
+use core::cell::Cell;
 use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
 use core::mem;
+use core::ptr;
+
+use bitflags::bitflags;
 use objc2::ffi::{NSInteger, NSUInteger};
 use objc2::rc::{autoreleasepool, AutoreleasePool, Id, Owned, Shared};
-use objc2::runtime::Object;
+use objc2::runtime::{Bool, Object, Sel};
 use objc2::{class, msg_send, sel};
 use objc2::{Encoding, Message, RefEncode};
-use objc2_foundation::NSString;
-
-use super::menuitem::NSMenuItem;
-
-struct MenuDelegate;
-
-struct USize {
-    height: f64,
-    width: f64,
+use objc2_foundation::{CGFloat, NSPoint, NSSize, NSString};
+
+use super::appearance::Appearance;
+use super::associated;
+use super::availability;
+use super::delegate::{self, MenuDelegate, MenuObserver};
+use super::event::NSEvent;
+use super::font::NSFont;
+use super::keyequivalent::KeyModifiers;
+use super::leaks;
+use super::menuitem::{MenuItemState, NSMenuItem};
+use super::view::NSView;
+
+bitflags! {
+    /// The properties of menu items that AppKit needs to be up to date, see
+    /// [`NSMenu::properties_to_update`].
+    #[doc(alias = "NSMenuProperties")]
+    pub struct MenuProperties: NSUInteger {
+        #[doc(alias = "NSMenuPropertyItemTitle")]
+        const TITLE = 1 << 0;
+        #[doc(alias = "NSMenuPropertyItemAttributedTitle")]
+        const ATTRIBUTED_TITLE = 1 << 1;
+        #[doc(alias = "NSMenuPropertyItemKeyEquivalent")]
+        const KEY_EQUIVALENT = 1 << 2;
+        #[doc(alias = "NSMenuPropertyItemImage")]
+        const IMAGE = 1 << 3;
+        #[doc(alias = "NSMenuPropertyItemEnabled")]
+        const ENABLED = 1 << 4;
+        #[doc(alias = "NSMenuPropertyItemAccessibilityDescription")]
+        const ACCESSIBILITY_DESCRIPTION = 1 << 5;
+    }
 }
 
 /// The maximum number of items a menu can hold is 65534
 #[repr(C)]
 pub struct NSMenu {
     _priv: [u8; 0],
+    /// Makes the menu `!Send` and `!Sync`, since its delegate can hold
+    /// closures that are neither
+    _thread: PhantomData<*mut ()>,
 }
 
 unsafe impl RefEncode for NSMenu {
@@ -29,9 +59,6 @@ unsafe impl RefEncode for NSMenu {
 
 unsafe impl Message for NSMenu {}
 
-unsafe impl Send for NSMenu {}
-unsafe impl Sync for NSMenu {}
-
 /// Creating menus
 impl NSMenu {
     fn alloc() -> *mut Self {
@@ -40,7 +67,9 @@ impl NSMenu {
 
     pub fn new() -> Id<Self, Owned> {
         let ptr = Self::alloc();
-        unsafe { Id::new(msg_send![ptr, init]).unwrap() }
+        let menu: Id<Self, Owned> = unsafe { Id::new(msg_send![ptr, init]).unwrap() };
+        leaks::track(&*menu, "NSMenu");
+        menu
     }
 
     // Public only locally to allow for construction in Menubar
@@ -49,19 +78,93 @@ impl NSMenu {
     pub(super) fn new_with_title(title: &str) -> Id<Self, Owned> {
         let title = NSString::from_str(title);
         let ptr = Self::alloc();
-        unsafe { Id::new(msg_send![ptr, initWithTitle: &*title]).unwrap() }
+        let menu: Id<Self, Owned> =
+            unsafe { Id::new(msg_send![ptr, initWithTitle: &*title]).unwrap() };
+        leaks::track(&*menu, "NSMenu");
+        menu
+    }
+
+    /// Take ownership of a menu created elsewhere, e.g. loaded from a nib or
+    /// created by other Objective-C code, to use the methods here on it.
+    ///
+    /// The menu is retained, so the caller keeps its own reference. Returns
+    /// `None` if `ptr` is null.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must be null or point to a valid `NSMenu` (or subclass).
+    /// - Since the returned handle allows mutation, no other code may access
+    ///   the menu while the handle exists, except through this handle.
+    /// - Like all menus, it must only be used from the main thread.
+    pub unsafe fn from_raw(ptr: *mut Object) -> Option<Id<Self, Owned>> {
+        Id::retain(ptr.cast())
+    }
+
+    /// Get a shared handle to a menu created elsewhere, for reading or
+    /// changing it through the methods on `&NSMenu`.
+    ///
+    /// Returns `None` if `object` is not an `NSMenu`.
+    ///
+    /// # Safety
+    ///
+    /// - The menu must not be mutated through an owned handle (like one from
+    ///   [`from_raw`]) while the returned handle exists.
+    /// - Like all menus, it must only be used from the main thread.
+    ///
+    /// [`from_raw`]: Self::from_raw
+    pub unsafe fn retain(object: &Object) -> Option<Id<Self, Shared>> {
+        let is_menu: Bool = msg_send![object, isKindOfClass: class!(NSMenu)];
+        if !is_menu.is_true() {
+            return None;
+        }
+        Id::retain(object as *const Object as *mut Self)
+    }
+
+    /// Create a copy of the menu, recursively copying its items and their
+    /// submenus, e.g. to use one context menu as a template for each row of
+    /// a table.
+    ///
+    /// The menu's font, minimum width, appearance and other properties are
+    /// copied, as are the titles, key equivalents, states, images, tags and
+    /// other properties of the items. Actions can't be copied in a meaningful way,
+    /// so the copied items share their targets and actions with the original
+    /// items instead, and closures set with [`NSMenuItem::set_action`] are
+    /// run for both. Custom views (except for items that [keep the menu
+    /// open](NSMenuItem::set_keeps_menu_open)) and delegates are not copied.
+    pub fn duplicate(&self) -> Id<Self, Owned> {
+        autoreleasepool(|pool| {
+            let mut menu = Self::new_with_title(&self.title());
+            menu.set_autoenables_items(self.autoenables_items());
+            menu.set_font(self.font(pool));
+            menu.set_minimum_width(self.minimum_width());
+            menu.set_shows_state_column(self.shows_state_column());
+            menu.set_allows_context_menu_plugins(self.allows_context_menu_plugins());
+            let this = unsafe { &*(self as *const Self as *const Object) };
+            if availability::responds_to(this, sel!(appearance)) {
+                let appearance: *mut Object = unsafe { msg_send![self, appearance] };
+                let _: () = unsafe { msg_send![&mut *menu, setAppearance: appearance] };
+            }
+            for item in self.iter(pool) {
+                menu.add(duplicate_item(item, pool));
+            }
+            menu
+        })
     }
 
-    // Title (only useful for MenuBar!)
+    // Title (mostly useful for MenuBar, where it is what is shown in the bar)
 
-    pub(super) fn title<'p>(&self, pool: &'p AutoreleasePool) -> &'p str {
-        let title: &'p NSString = unsafe { msg_send![self, title] };
-        title.as_str(pool)
+    /// The title of the menu.
+    pub fn title(&self) -> String {
+        autoreleasepool(|pool| {
+            let title: &NSString = unsafe { msg_send![self, title] };
+            title.as_str(pool).to_owned()
+        })
     }
 
+    /// Change the title of the menu, e.g. to rename a submenu at runtime.
     #[doc(alias = "setTitle")]
     #[doc(alias = "setTitle:")]
-    pub(super) fn set_title(&mut self, title: &str) {
+    pub fn set_title(&mut self, title: &str) {
         let title = NSString::from_str(title);
         unsafe { msg_send![self, setTitle: &*title] }
     }
@@ -71,6 +174,12 @@ impl NSMenu {
 impl NSMenu {
     /// Insert an item at the specified index.
     ///
+    /// Inserting at `index == menu.len()` is equivalent to [`add`].
+    ///
+    /// [`add`]: Self::add
+    ///
+    /// # Panics
+    ///
     /// Panics if `index > menu.len()`.
     #[doc(alias = "insertItem")]
     #[doc(alias = "insertItem:atIndex:")]
@@ -95,6 +204,11 @@ impl NSMenu {
         item.into()
     }
 
+    /// Append an item to the menu.
+    ///
+    /// Returns a shared handle to the item, which can be kept around to
+    /// change e.g. its state or title later on, without having to find it
+    /// again by tag or title.
     #[doc(alias = "addItem")]
     #[doc(alias = "addItem:")]
     pub fn add(&mut self, item: Id<NSMenuItem, Owned>) -> Id<NSMenuItem, Shared> {
@@ -104,20 +218,164 @@ impl NSMenu {
         item.into()
     }
 
+    /// Append many items at once, e.g. when building a large "Open Recent"
+    /// menu from a list.
+    ///
+    /// Change notifications are suspended while the items are added, so
+    /// observers (and AppKit's own layout) only react once at the end,
+    /// instead of once per item.
+    #[doc(alias = "setMenuChangedMessagesEnabled")]
+    #[doc(alias = "setMenuChangedMessagesEnabled:")]
+    pub fn add_all(&mut self, items: impl IntoIterator<Item = Id<NSMenuItem, Owned>>) {
+        let enabled: Bool = unsafe { msg_send![&*self, menuChangedMessagesEnabled] };
+        let _: () = unsafe { msg_send![&mut *self, setMenuChangedMessagesEnabled: Bool::NO] };
+        for item in items {
+            let _ = self.add(item);
+        }
+        // Re-enabling flushes the pending notifications
+        let _: () = unsafe { msg_send![self, setMenuChangedMessagesEnabled: enabled] };
+    }
+
+    /// Create an item with the given title, and append it to the menu.
+    ///
+    /// See [`add_item_with_key`] and [`add_item_with_action`] for setting up
+    /// a key equivalent or action at the same time.
+    ///
+    /// [`add_item_with_key`]: Self::add_item_with_key
+    /// [`add_item_with_action`]: Self::add_item_with_action
+    pub fn add_item(&mut self, title: &str) -> Id<NSMenuItem, Shared> {
+        self.add(NSMenuItem::new(title, "", None))
+    }
+
+    /// Create an item with the given title and key equivalent, and append it
+    /// to the menu.
+    ///
+    /// See [`NSMenuItem::set_key_equivalent`] for how `key` and `modifiers`
+    /// are interpreted.
+    pub fn add_item_with_key(
+        &mut self,
+        title: &str,
+        key: &str,
+        modifiers: KeyModifiers,
+    ) -> Id<NSMenuItem, Shared> {
+        let mut item = NSMenuItem::new(title, "", None);
+        item.set_key_equivalent(key, modifiers);
+        self.add(item)
+    }
+
+    /// Create an item with the given title and key equivalent, that calls
+    /// `handler` when selected, and append it to the menu.
+    ///
+    /// Pass an empty `key` for an item without a key equivalent.
+    pub fn add_item_with_action(
+        &mut self,
+        title: &str,
+        key: &str,
+        modifiers: KeyModifiers,
+        handler: impl Fn() + 'static,
+    ) -> Id<NSMenuItem, Shared> {
+        let mut item = NSMenuItem::new(title, "", None);
+        item.set_key_equivalent(key, modifiers);
+        item.set_action(handler);
+        self.add(item)
+    }
+
+    /// Append a separator item to the menu, returning a handle to it, e.g.
+    /// to hide it later.
+    pub fn add_separator(&mut self) -> Id<NSMenuItem, Shared> {
+        self.add(NSMenuItem::new_separator())
+    }
+
+    /// Insert a separator item at the specified index, e.g. to split a group
+    /// of items in a menu that is edited in place, see [`insert`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > menu.len()`.
+    ///
+    /// [`insert`]: Self::insert
+    pub fn insert_separator(&mut self, index: usize) -> Id<NSMenuItem, Shared> {
+        self.insert(NSMenuItem::new_separator(), index)
+    }
+
     // There exists `addItemWithTitle_action_keyEquivalent`
 
-    // Can't use this yet, we need to find a way to let users have references to menu items safely!
-    // #[doc(alias = "removeItem")]
-    // #[doc(alias = "removeItem:")]
-    // fn remove(&mut self, item: &mut NSMenuItem) {
-    //     unsafe { msg_send![self, removeItem: item] }
-    // }
-    // #[doc(alias = "removeItemAtIndex")]
-    // #[doc(alias = "removeItemAtIndex:")]
-    // fn remove_at_index(&mut self, at: isize) {
-    //     unimplemented!()
-    // }
+    /// Remove the given item from the menu.
+    ///
+    /// Does nothing if the item is not in this menu.
+    #[doc(alias = "removeItem")]
+    #[doc(alias = "removeItem:")]
+    pub fn remove(&mut self, item: &NSMenuItem) {
+        let index: NSInteger = unsafe { msg_send![&*self, indexOfItem: item] };
+        if index < 0 {
+            return;
+        }
+        unsafe { msg_send![self, removeItemAtIndex: index] }
+    }
+
+    /// Remove the item at the specified index, and return it.
+    ///
+    /// The returned item is shared, since other handles to it (e.g. the one
+    /// returned from [`add`]) may still exist.
+    ///
+    /// [`add`]: Self::add
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= menu.len()`.
+    #[doc(alias = "removeItemAtIndex")]
+    #[doc(alias = "removeItemAtIndex:")]
+    pub fn remove_at(&mut self, index: usize) -> Id<NSMenuItem, Shared> {
+        let length = self.len();
+        if index >= length {
+            panic!(
+                "Failed removing item: Index {} out of bounds for number of items {}",
+                index, length
+            );
+        }
+        let index = index as NSInteger;
+        // Retain the item before removing it, since the menu releases it
+        let item: *mut NSMenuItem = unsafe { msg_send![&*self, itemAtIndex: index] };
+        let item = unsafe { Id::retain(item).unwrap() };
+        let _: () = unsafe { msg_send![self, removeItemAtIndex: index] };
+        item
+    }
+
+    /// Move the item at index `from` to index `to`, shifting the items in
+    /// between, like removing it and then inserting it at `to`.
+    ///
+    /// The item itself is moved, so its submenu, action and other
+    /// properties are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from >= menu.len()` or `to >= menu.len()`.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        let length = self.len();
+        for &index in &[from, to] {
+            if index >= length {
+                panic!(
+                    "Failed moving item: Index {} out of bounds for number of items {}",
+                    index, length
+                );
+            }
+        }
+        if from == to {
+            return;
+        }
+        // Keep the item alive while it is not in the menu
+        let item = self.remove_at(from);
+        let _: () = unsafe { msg_send![self, insertItem: &*item, atIndex: to as NSInteger] };
+    }
 
+    /// Remove every item from the menu at once.
+    ///
+    /// This is much faster than removing the items one by one, and is
+    /// useful for menus that are rebuilt every time they are opened.
+    ///
+    /// The removed items release their submenus, so they are not leaked
+    /// unless a handle to them is held elsewhere.
+    ///
     /// Does not post notifications.
     #[doc(alias = "removeAllItems")]
     pub fn remove_all(&mut self) {
@@ -127,44 +385,63 @@ impl NSMenu {
 
     // Finding items
 
+    /// Find the first item with the given [tag](NSMenuItem::tag).
     #[doc(alias = "itemWithTag")]
     #[doc(alias = "itemWithTag:")]
-    fn find_by_tag<'p>(&self, pool: &'p AutoreleasePool, tag: isize) -> Option<&'p NSMenuItem> {
-        unimplemented!()
+    pub fn item_with_tag<'p>(
+        &self,
+        pool: &'p AutoreleasePool,
+        tag: isize,
+    ) -> Option<&'p NSMenuItem> {
+        unsafe { msg_send![self, itemWithTag: tag as NSInteger] }
     }
 
+    /// Find the first item with the given title.
+    ///
+    /// The match is exact and case-sensitive.
     #[doc(alias = "itemWithTitle")]
     #[doc(alias = "itemWithTitle:")]
-    fn find_by_title<'p>(&self, pool: &'p AutoreleasePool, title: &str) -> Option<&'p NSMenuItem> {
-        unimplemented!()
+    pub fn item_with_title<'p>(
+        &self,
+        pool: &'p AutoreleasePool,
+        title: &str,
+    ) -> Option<&'p NSMenuItem> {
+        let title = NSString::from_str(title);
+        unsafe { msg_send![self, itemWithTitle: &*title] }
     }
 
+    /// Get the item at the specified index, or `None` if out of bounds.
     #[doc(alias = "itemAtIndex")]
     #[doc(alias = "itemAtIndex:")]
-    unsafe fn get_at_index<'p>(&self, pool: &'p AutoreleasePool, at: isize) -> &'p NSMenuItem {
-        unimplemented!()
+    pub fn item_at<'p>(&self, pool: &'p AutoreleasePool, index: usize) -> Option<&'p NSMenuItem> {
+        // `itemAtIndex:` throws an exception when out of bounds
+        if index >= self.len() {
+            return None;
+        }
+        unsafe { msg_send![self, itemAtIndex: index as NSInteger] }
     }
 
     // Getting all items
 
-    /// Number of items in this menu, including separators
+    /// Number of items in this menu, including separators and hidden items
     #[doc(alias = "numberOfItems")]
     pub fn len(&self) -> usize {
         let number_of_items: NSInteger = unsafe { msg_send![self, numberOfItems] };
         number_of_items as usize
     }
 
+    /// Whether the menu has no items (not even separators or hidden items)
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
+    /// Iterate over the items in the menu, in the order they are shown.
+    ///
+    /// The iterator works on a snapshot of the items, so modifying the menu
+    /// while iterating does not affect it.
     #[doc(alias = "itemArray")]
-    fn get_all_items<'p>(&self, pool: &'p AutoreleasePool) -> &'p [&'p NSMenuItem] {
-        unimplemented!()
-    }
-
-    #[doc(alias = "itemArray")]
+    #[doc(alias = "items")]
     pub fn iter<'p>(&self, pool: &'p AutoreleasePool) -> impl Iterator<Item = &'p NSMenuItem> + 'p {
         let array: *const Object = unsafe { msg_send![self, itemArray] };
         let enumerator: *mut Object = unsafe { msg_send![array, objectEnumerator] };
@@ -177,77 +454,178 @@ impl NSMenu {
 
     // Finding indices of elements
 
+    /// The index of the given item, or `None` if it is not in this menu.
+    ///
+    /// Useful e.g. to insert a sibling right after an item.
     #[doc(alias = "indexOfItem")]
     #[doc(alias = "indexOfItem:")]
-    fn index_of(&self, item: &NSMenuItem) -> Option<isize> {
-        unimplemented!()
+    pub fn index_of_item(&self, item: &NSMenuItem) -> Option<usize> {
+        let index: NSInteger = unsafe { msg_send![self, indexOfItem: item] };
+        index_from_raw(index)
     }
 
+    /// The index of the first item with the given title.
+    ///
+    /// The match is exact and case-sensitive.
     #[doc(alias = "indexOfItemWithTitle")]
     #[doc(alias = "indexOfItemWithTitle:")]
-    fn index_of_by_title(&self, title: &str) -> Option<isize> {
-        unimplemented!()
+    pub fn index_of_item_with_title(&self, title: &str) -> Option<usize> {
+        let title = NSString::from_str(title);
+        let index: NSInteger = unsafe { msg_send![self, indexOfItemWithTitle: &*title] };
+        index_from_raw(index)
     }
 
+    /// The index of the first item with the given [tag](NSMenuItem::tag).
     #[doc(alias = "indexOfItemWithTag")]
     #[doc(alias = "indexOfItemWithTag:")]
-    fn index_of_by_tag(&self, tag: isize) -> Option<isize> {
-        unimplemented!()
+    pub fn index_of_item_with_tag(&self, tag: isize) -> Option<usize> {
+        let index: NSInteger = unsafe { msg_send![self, indexOfItemWithTag: tag as NSInteger] };
+        index_from_raw(index)
+    }
+
+    /// The index of the first item with the given target and action.
+    ///
+    /// If `action` is `None`, the first item with the given target is
+    /// found.
+    #[doc(alias = "indexOfItemWithTarget")]
+    #[doc(alias = "indexOfItemWithTarget:andAction:")]
+    pub fn index_of_item_with_target_and_action(
+        &self,
+        target: Option<&Object>,
+        action: Option<Sel>,
+    ) -> Option<usize> {
+        let action = match action {
+            Some(action) => action.as_ptr(),
+            None => ptr::null(),
+        };
+        let index: NSInteger =
+            unsafe { msg_send![self, indexOfItemWithTarget: target, andAction: action] };
+        index_from_raw(index)
     }
 
-    // fn index_of_by_action_and_target(&self, ...) -> isize {}
-    // fn index_of_item_by_represented_object(&self, ...) -> isize {}
+    /// The index of the first item whose
+    /// [represented object](NSMenuItem::represented_object) is the given
+    /// object.
+    #[doc(alias = "indexOfItemWithRepresentedObject")]
+    #[doc(alias = "indexOfItemWithRepresentedObject:")]
+    pub fn index_of_item_with_represented_object(&self, object: &Object) -> Option<usize> {
+        let index: NSInteger = unsafe { msg_send![self, indexOfItemWithRepresentedObject: object] };
+        index_from_raw(index)
+    }
 
+    /// The index of the item with the given submenu.
     #[doc(alias = "indexOfItemWithSubmenu")]
     #[doc(alias = "indexOfItemWithSubmenu:")]
-    fn index_of_submenu(&self, submenu: &NSMenu) -> Option<isize> {
-        unimplemented!()
+    pub fn index_of_item_with_submenu(&self, submenu: &NSMenu) -> Option<usize> {
+        let index: NSInteger = unsafe { msg_send![self, indexOfItemWithSubmenu: submenu] };
+        index_from_raw(index)
     }
 
     // Managing submenus
 
+    /// Set the submenu of an item in this menu, e.g. one the crate didn't
+    /// create.
+    ///
+    /// See also [`NSMenuItem::set_submenu`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `submenu` is already the submenu of an item in another
+    /// menu.
     #[doc(alias = "setSubmenu")]
     #[doc(alias = "setSubmenu:forItem:")]
-    // Unsure about this!
-    fn set_submenu(&self, submenu: &mut NSMenu, for_item: &mut NSMenuItem) {
-        unimplemented!()
+    pub fn set_submenu_for_item(&mut self, submenu: &NSMenu, item: &NSMenuItem) {
+        // AppKit throws an exception in this case
+        let supermenu: *const NSMenu = unsafe { msg_send![submenu, supermenu] };
+        if !supermenu.is_null() && !ptr::eq(supermenu, self) {
+            panic!("Failed setting submenu: The menu is already a submenu in another menu");
+        }
+        unsafe { msg_send![self, setSubmenu: submenu, forItem: item] }
     }
 
     // fn submenuAction(&self) {} // Overridable!
 
-    #[doc(alias = "supermenu")]
-    fn get_parent<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenu> {
-        unimplemented!()
+    /// The menu containing the item whose submenu is this menu, or `None`
+    /// if this is not a submenu.
+    ///
+    /// Together with [`NSMenuItem::menu`], this can be used to walk up from
+    /// an item to the root menu.
+    pub fn supermenu<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenu> {
+        unsafe { msg_send![self, supermenu] }
     }
 
     // Has more deprecated methods!
 
     // Enable/disable items
 
-    /// Default on
+    /// Whether the menu automatically enables and disables its items.
+    ///
+    /// Default on.
     #[doc(alias = "autoenablesItems")]
-    fn autoenables_items(&self) -> bool {
-        unimplemented!()
+    pub fn autoenables_items(&self) -> bool {
+        let autoenables: Bool = unsafe { msg_send![self, autoenablesItems] };
+        autoenables.is_true()
     }
 
+    /// Set whether the menu automatically enables and disables its items.
+    ///
+    /// While on, AppKit decides whether each item is enabled every time the
+    /// menu is validated (based on whether the item's target responds to its
+    /// action), and any value set with [`NSMenuItem::set_enabled`] is
+    /// overwritten. Turn this off to control enablement manually.
     #[doc(alias = "setAutoenablesItems")]
     #[doc(alias = "setAutoenablesItems:")]
-    fn set_autoenables_items(&mut self, state: bool) {
-        unimplemented!()
+    pub fn set_autoenables_items(&mut self, autoenables: bool) {
+        let autoenables = Bool::new(autoenables);
+        unsafe { msg_send![self, setAutoenablesItems: autoenables] }
     }
 
-    #[doc(alias = "update")]
-    fn update_enabled_state_of_items(&self) {
-        unimplemented!()
+    /// Validate the items immediately, updating their enabled state if
+    /// [`autoenables_items`](Self::autoenables_items) is set.
+    ///
+    /// This normally happens automatically before the menu is shown, so this
+    /// is only needed when the state must be consistent synchronously, e.g.
+    /// right after changing many items.
+    pub fn update(&self) {
+        unsafe { msg_send![self, update] }
     }
 
     // Control fonts for this and subitems
 
-    // fn font() -> Font {}
+    /// The font used for the items in the menu.
+    pub fn font<'p>(&self, pool: &'p AutoreleasePool) -> &'p NSFont {
+        unsafe { msg_send![self, font] }
+    }
+
+    /// Set the font used for the items in the menu.
+    ///
+    /// This also affects submenus, unless they set a font of their own.
+    #[doc(alias = "setFont")]
+    #[doc(alias = "setFont:")]
+    pub fn set_font(&mut self, font: &NSFont) {
+        unsafe { msg_send![self, setFont: font] }
+    }
 
-    // #[doc(alias = "setFont")]
-    // #[doc(alias = "setFont:")]
-    // fn set_font(&mut self, font: Font) {}
+    /// Force the menu (and its submenus) to be drawn with a specific
+    /// appearance, instead of following the system setting.
+    ///
+    /// This only affects menus when they're open, e.g. submenus of the menu
+    /// bar, context menus and pop-up menus. The menu bar itself is always
+    /// drawn by the system, based on the system setting and desktop picture,
+    /// and ignores this.
+    ///
+    /// Does nothing on macOS versions older than 10.14, where menus don't
+    /// support appearances.
+    #[doc(alias = "setAppearance")]
+    #[doc(alias = "setAppearance:")]
+    pub fn set_appearance(&mut self, appearance: Appearance) {
+        let this = unsafe { &*(self as *const Self as *const Object) };
+        if !availability::responds_to(this, sel!(setAppearance:)) {
+            return;
+        }
+        let appearance = appearance.to_raw();
+        unsafe { msg_send![self, setAppearance: appearance] }
+    }
 
     // Handling keyboard events
 
@@ -255,73 +633,130 @@ impl NSMenu {
     // #[doc(alias = "performKeyEquivalent:")]
     // fn perform_key_equivalent(&self, event: KeyEvent) -> bool {}
 
-    // Simulating mouse clicks
-
-    // #[doc(alias = "performActionForItemAtIndex")]
-    // #[doc(alias = "performActionForItemAtIndex:")]
-    // fn perform_action_for_item_at(&self, index: isize) {}
-
     // Size
 
+    /// The minimum width of the menu in points, `0.0` when not set.
     #[doc(alias = "minimumWidth")]
-    fn min_width(&self) -> Option<f64> {
-        // None / zero when not set
-        unimplemented!()
+    pub fn minimum_width(&self) -> f64 {
+        let width: CGFloat = unsafe { msg_send![self, minimumWidth] };
+        width as f64
     }
 
+    /// Set the minimum width of the menu in points, to prevent it from
+    /// changing width as its content changes.
+    ///
+    /// AppKit may still make the menu wider to fit its content.
     #[doc(alias = "setMinimumWidth")]
     #[doc(alias = "setMinimumWidth:")]
-    fn set_min_width(&mut self, width: Option<f64>) {
-        // None ~= zero
-        unimplemented!()
+    pub fn set_minimum_width(&mut self, width: f64) {
+        let width = width as CGFloat;
+        unsafe { msg_send![self, setMinimumWidth: width] }
     }
 
-    fn size(&self) -> USize {
-        unimplemented!()
-    }
-
-    #[doc(alias = "setSize")]
-    #[doc(alias = "setSize:")]
-    fn set_size(&mut self, size: USize) {
-        // Might change the size if too big (or small?)
-        unimplemented!()
+    /// The width and height of the menu in points.
+    ///
+    /// The menu is laid out for its current items if needed, so this can be
+    /// used before the menu is shown, e.g. to position a pop-up menu.
+    pub fn size(&self) -> (f64, f64) {
+        let size: NSSize = unsafe { msg_send![self, size] };
+        (size.width() as f64, size.height() as f64)
     }
 
     // propertiesToUpdate - for efficiency when updating items
 
+    /// Whether third-party contextual menu plug-ins may add items when the
+    /// menu is shown as a context menu.
+    ///
+    /// Default on.
     #[doc(alias = "allowsContextMenuPlugIns")]
-    fn allows_context_menu_plug_ins(&self) -> bool {
-        unimplemented!()
+    pub fn allows_context_menu_plugins(&self) -> bool {
+        let allows: Bool = unsafe { msg_send![self, allowsContextMenuPlugIns] };
+        allows.is_true()
     }
 
+    /// Set whether third-party contextual menu plug-ins may add items, see
+    /// [`pop_up_context_menu`](Self::pop_up_context_menu).
     #[doc(alias = "setAllowsContextMenuPlugIns")]
     #[doc(alias = "setAllowsContextMenuPlugIns:")]
-    fn set_allows_context_menu_plug_ins(&mut self, state: bool) {
-        unimplemented!()
+    pub fn set_allows_context_menu_plugins(&mut self, allows: bool) {
+        let allows = Bool::new(allows);
+        unsafe { msg_send![self, setAllowsContextMenuPlugIns: allows] }
+    }
+
+    /// Show the menu as a context menu at the location of `event`, which
+    /// is usually the right mouse click that triggered it.
+    ///
+    /// Does not return until the menu is closed.
+    #[doc(alias = "popUpContextMenu")]
+    #[doc(alias = "popUpContextMenu:withEvent:forView:")]
+    pub fn pop_up_context_menu(&self, event: &NSEvent, view: &NSView) {
+        unsafe {
+            msg_send![
+                class!(NSMenu),
+                popUpContextMenu: self,
+                withEvent: event,
+                forView: view,
+            ]
+        }
     }
 
-    // #[doc(alias = "popUpContextMenu:withEvent:forView:")]
-    // fn displayPopUpContextMenu(&mut self, event: Event, view: Option<&View>) {}
     // #[doc(alias = "popUpContextMenu:withEvent:forView:withFont:")]
     // fn displayPopUpContextMenuWithFont(&mut self, event: Event, view: Option<&View>, font: Font) {}
-    // #[doc(alias = "popUpMenuPositioningItem:atLocation:inView:")]
-    // fn displayPopUpAtMenuPositioningItem(&mut self, position_item: Option<&NSMenuItem>, event: Event, view: Option<&View>)
 
-    // Whether the menu displays the state column (the "Checkmark" column for items?)
+    /// Show the menu as a pop-up, such that `item` is located at `location`.
+    ///
+    /// The location is in the coordinate system of `view`, or in screen
+    /// coordinates if `view` is `None`. If `item` is `None`, the top left
+    /// corner of the menu is placed at `location`.
+    ///
+    /// Does not return until the menu is closed, and returns whether an item
+    /// was selected.
+    #[doc(alias = "popUpMenuPositioningItem")]
+    #[doc(alias = "popUpMenuPositioningItem:atLocation:inView:")]
+    pub fn pop_up_positioning(
+        &self,
+        item: Option<&NSMenuItem>,
+        location: (f64, f64),
+        view: Option<&NSView>,
+    ) -> bool {
+        let location = NSPoint::new(location.0 as CGFloat, location.1 as CGFloat);
+        let selected: Bool = unsafe {
+            msg_send![
+                self,
+                popUpMenuPositioningItem: item,
+                atLocation: location,
+                inView: view,
+            ]
+        };
+        selected.is_true()
+    }
+
+    /// Whether space is reserved for the state column, where checkmarks
+    /// are shown.
+    ///
+    /// Default on.
     #[doc(alias = "showsStateColumn")]
-    fn show_state_column(&self) -> bool {
-        unimplemented!()
+    pub fn shows_state_column(&self) -> bool {
+        let shows: Bool = unsafe { msg_send![self, showsStateColumn] };
+        shows.is_true()
     }
 
+    /// Set whether space is reserved for the state column.
+    ///
+    /// Turning it off gives a tighter layout for menus without stateful
+    /// items.
     #[doc(alias = "setShowsStateColumn")]
     #[doc(alias = "setShowsStateColumn:")]
-    fn set_show_state_column(&mut self, show: bool) {
-        unimplemented!()
+    pub fn set_shows_state_column(&mut self, shows: bool) {
+        let shows = Bool::new(shows);
+        unsafe { msg_send![self, setShowsStateColumn: shows] }
     }
 
+    /// The item the user is currently hovering, or `None` if the menu is
+    /// not open or no item is highlighted.
     #[doc(alias = "highlightedItem")]
-    fn currently_highlighted_item<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenuItem> {
-        unimplemented!()
+    pub fn highlighted_item<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenuItem> {
+        unsafe { msg_send![self, highlightedItem] }
     }
 
     // Should honestly probably not be changed! (userInterfaceLayoutDirection)
@@ -330,40 +765,154 @@ impl NSMenu {
 
     // You can use the delegate to populate a menu just before it is drawn
     // and to check for key equivalents without creating a menu item.
-    fn delegate(&self) -> &MenuDelegate {
-        // Tied to a pool or the current item?
-        unimplemented!()
-
-        // Events / things this delegate can respond to
-        // - menuHasKeyEquivalent:forEvent:target:action:
-        // - menu:updateItem:atIndex:shouldCancel: (update_item_before_displayed)
-        // - confinementRectForMenu:onScreen: (display_location)
-        // - menu:willHighlightItem: (before_highlight_item)
-        // - menuWillOpen: (before_open)
-        // - menuDidClose: (after_close)
-        // - numberOfItemsInMenu: // Works together with updateItemBeforeDisplayed
-        //     Newly created items are blank, and then updateItemBeforeDisplayed populates them
-        // - menuNeedsUpdate: // Alternatively, if the population can happen basically instantly
-        //     (and don't need to do a lot of processing beforehand), this can just be used
-    }
-
+    //
+    // Events / things this delegate can respond to
+    // - menuHasKeyEquivalent:forEvent:target:action:
+    // - menu:updateItem:atIndex:shouldCancel: (update_item_before_displayed)
+    // - menu:willHighlightItem: (before_highlight_item)
+    // - menuWillOpen: (before_open)
+    // - menuDidClose: (after_close)
+    // - numberOfItemsInMenu: // Works together with updateItemBeforeDisplayed
+    //     Newly created items are blank, and then updateItemBeforeDisplayed populates them
+    // - menuNeedsUpdate: // Alternatively, if the population can happen basically instantly
+    //     (and don't need to do a lot of processing beforehand), this can just be used
+
+    /// Install callbacks that are run when the menu opens or closes.
+    ///
+    /// Replaces any previously set delegate, but keeps the observers added
+    /// with [`add_delegate_observer`]. The callbacks are kept alive for as
+    /// long as the menu is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within one of the previous delegate's
+    /// callbacks.
+    ///
+    /// [`add_delegate_observer`]: Self::add_delegate_observer
     #[doc(alias = "setDelegate")]
     #[doc(alias = "setDelegate:")]
-    fn set_delegate(&mut self, delegate: &mut MenuDelegate) {
-        unimplemented!()
+    pub fn set_delegate(&mut self, mut delegate: MenuDelegate) {
+        delegate.set_observers(delegate::take_observers(self));
+        let delegate = delegate::new(delegate);
+        // SAFETY: The menu is a valid object, and will retain the delegate
+        unsafe {
+            associated::set(
+                self as *const Self as *const Object,
+                &delegate::ASSOCIATION_KEY,
+                Some(&delegate),
+            )
+        };
+        unsafe { msg_send![self, setDelegate: &*delegate] }
+    }
+
+    /// Observe the menu opening, closing or changing its highlighted item,
+    /// without replacing the delegate or other observers.
+    ///
+    /// Any number of observers can be added; they are run after the
+    /// delegate's own callbacks, in the order they were added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within one of the delegate's or observers'
+    /// callbacks.
+    pub fn add_delegate_observer(&mut self, observer: MenuObserver) {
+        delegate::add_observer(self, observer);
+    }
+
+    /// Run `handler` whenever the highlighted item is about to change, see
+    /// [`MenuDelegate::on_highlight`].
+    ///
+    /// If a delegate has already been set with [`set_delegate`], its other
+    /// callbacks are kept.
+    ///
+    /// [`set_delegate`]: Self::set_delegate
+    pub fn set_highlight_handler(&mut self, handler: impl Fn(Option<&NSMenuItem>) + 'static) {
+        delegate::update(self, |callbacks| callbacks.set_highlight(handler));
+    }
+
+    /// Constrain where on the screen the menu may be shown, see
+    /// [`MenuDelegate::on_confine`].
+    ///
+    /// If a delegate has already been set with [`set_delegate`], its other
+    /// callbacks are kept.
+    ///
+    /// [`set_delegate`]: Self::set_delegate
+    pub fn set_confinement_handler(
+        &mut self,
+        handler: impl Fn() -> Option<(f64, f64, f64, f64)> + 'static,
+    ) {
+        delegate::update(self, |callbacks| callbacks.set_confinement(handler));
+    }
+
+    /// Populate the menu lazily, see [`MenuDelegate::on_populate`].
+    ///
+    /// If a delegate has already been set with [`set_delegate`], its other
+    /// callbacks are kept.
+    ///
+    /// [`set_delegate`]: Self::set_delegate
+    pub fn set_lazy_populator(
+        &mut self,
+        count: impl Fn() -> usize + 'static,
+        update: impl Fn(&NSMenu, &mut NSMenuItem, usize) -> bool + 'static,
+    ) {
+        delegate::update(self, |callbacks| callbacks.set_populate(count, update));
+    }
+
+    /// The item properties AppKit needs, while the menu is being updated.
+    ///
+    /// Check this on the menu passed to the update callback of
+    /// [`set_lazy_populator`] to only compute what is needed, e.g. to skip loading images when AppKit
+    /// is only matching key equivalents. Outside of an update, all
+    /// properties should be assumed to be needed.
+    ///
+    /// [`set_lazy_populator`]: Self::set_lazy_populator
+    #[doc(alias = "propertiesToUpdate")]
+    pub fn properties_to_update(&self) -> MenuProperties {
+        let properties: NSUInteger = unsafe { msg_send![self, propertiesToUpdate] };
+        MenuProperties::from_bits_truncate(properties)
     }
 
     // Handling tracking? Perhaps just means closing/dismissing the menu?
 
+    /// Close the menu if it is open, e.g. before showing modal UI.
+    ///
+    /// Does nothing if the menu is not currently being tracked.
     #[doc(alias = "cancelTracking")]
-    fn cancel_tracking(&mut self) {
-        unimplemented!()
+    pub fn cancel_tracking(&self) {
+        unsafe { msg_send![self, cancelTracking] }
     }
 
+    /// Close the menu immediately if it is open, without the fade-out
+    /// animation.
+    ///
+    /// Does nothing if the menu is not currently being tracked.
     #[doc(alias = "cancelTrackingWithoutAnimation")]
-    #[doc(alias = "cancelTrackingWithoutAnimation:")]
-    fn cancel_tracking_without_animation(&mut self) {
-        unimplemented!()
+    pub fn cancel_tracking_without_animation(&self) {
+        unsafe { msg_send![self, cancelTrackingWithoutAnimation] }
+    }
+
+    /// Simulate the user choosing the item at the given index.
+    ///
+    /// AppKit sends the action without checking whether the item is
+    /// enabled, but handlers set with [`NSMenuItem::set_action`] are only
+    /// run if the item is enabled and its validator (if any) allows it, like
+    /// when the item is clicked. Useful for testing the wiring of a menu
+    /// without user input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[doc(alias = "performActionForItemAtIndex")]
+    #[doc(alias = "performActionForItemAtIndex:")]
+    pub fn perform_action_for_item_at(&self, index: usize) {
+        let length = self.len();
+        if index >= length {
+            panic!(
+                "Failed performing action: Index {} out of bounds for number of items {}",
+                index, length
+            );
+        }
+        unsafe { msg_send![self, performActionForItemAtIndex: index as NSInteger] }
     }
 
     // "Notifications" - not sure what these are yet!
@@ -380,6 +929,8 @@ impl NSMenu {
     //     NSUserInterfaceItemIdentification - May become important!
 }
 
+/// Equality is reference identity: two values are equal only if they are
+/// the same object, not if e.g. their titles are the same.
 impl PartialEq for NSMenu {
     /// Pointer equality
     #[inline]
@@ -388,20 +939,93 @@ impl PartialEq for NSMenu {
     }
 }
 
+impl Eq for NSMenu {}
+
+impl Hash for NSMenu {
+    /// Hashes the pointer, consistent with [`PartialEq`]
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self as *const Self).hash(state)
+    }
+}
+
+/// How many levels of submenus are shown when debug-formatting a menu.
+const MAX_DEBUG_DEPTH: usize = 8;
+
+std::thread_local! {
+    static DEBUG_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
 impl fmt::Debug for NSMenu {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         autoreleasepool(|pool| {
-            f.debug_struct("NSMenu")
+            let mut debug = f.debug_struct("NSMenu");
+            debug
                 .field("id", &(self as *const Self))
-                .field("title", &self.title(pool))
-                // TODO: parent?
-                // TODO: size and stuff
-                .field("items", &self.iter(pool).collect::<Vec<_>>())
-                .finish()
+                .field("title", &self.title());
+            // TODO: parent?
+            // TODO: size and stuff
+            // Items print their submenus, so limit the recursion
+            let depth = DEBUG_DEPTH.with(Cell::get);
+            if depth < MAX_DEBUG_DEPTH {
+                DEBUG_DEPTH.with(|d| d.set(depth + 1));
+                debug.field("items", &self.iter(pool).collect::<Vec<_>>());
+                DEBUG_DEPTH.with(|d| d.set(depth));
+            } else {
+                debug.field("items", &format_args!(".."));
+            }
+            debug.finish()
         })
     }
 }
 
+/// Copy `item` for [`NSMenu::duplicate`].
+fn duplicate_item(item: &NSMenuItem, pool: &AutoreleasePool) -> Id<NSMenuItem, Owned> {
+    let mut copy = if item.is_separator() {
+        NSMenuItem::new_separator()
+    } else {
+        NSMenuItem::new(item.title(pool), "", None)
+    };
+    copy.set_attributed_title(item.attributed_title(pool));
+    copy.set_key_equivalent(
+        item.key_equivalent(pool),
+        item.key_equivalent_modifier_mask(),
+    );
+    copy.set_allows_key_equivalent_when_hidden(item.allows_key_equivalent_when_hidden());
+    copy.set_alternate(item.is_alternate());
+    copy.set_state(item.state());
+    copy.set_enabled(item.is_enabled());
+    copy.set_hidden(item.is_hidden());
+    copy.set_tag(item.tag());
+    copy.set_image(item.image(pool));
+    for state in [MenuItemState::On, MenuItemState::Mixed, MenuItemState::Off] {
+        copy.set_image_for_state(state, item.image_for_state(pool, state));
+    }
+    copy.set_indentation_level(item.indentation_level());
+    copy.set_tooltip(item.tooltip(pool));
+    copy.set_represented_object(item.represented_object(pool));
+
+    let target: *const Object = unsafe { msg_send![item, target] };
+    let action: Sel = unsafe { msg_send![item, action] };
+    copy.set_target_action(unsafe { target.as_ref() }, action);
+
+    if let Some(submenu) = item.submenu(pool) {
+        copy.set_submenu(Some(submenu.duplicate()));
+    }
+    // Set last, since the view draws the title it had when created
+    copy.set_keeps_menu_open(item.keeps_menu_open());
+    copy
+}
+
+/// AppKit uses `-1` to signal that an item was not found.
+fn index_from_raw(index: NSInteger) -> Option<usize> {
+    if index < 0 {
+        None
+    } else {
+        Some(index as usize)
+    }
+}
+
 struct Iter<'p> {
     array: *const Object,
     enumerator: *mut Object,
@@ -438,44 +1062,964 @@ mod tests {
 
     #[test]
     fn test_title() {
-        autoreleasepool(|pool| {
+        autoreleasepool(|_| {
             let mut menu = NSMenu::new();
-            assert_eq!(menu.title(pool), "");
+            assert_eq!(menu.title(), "");
             STRINGS.iter().for_each(|&title| {
                 menu.set_title(title);
-                assert_eq!(menu.title(pool), title);
+                assert_eq!(menu.title(), title);
             });
         });
     }
 
     #[test]
     fn test_title_init() {
-        autoreleasepool(|pool| {
+        autoreleasepool(|_| {
             STRINGS.iter().for_each(|&title| {
                 let menu = NSMenu::new_with_title(title);
-                assert_eq!(menu.title(pool), title);
+                assert_eq!(menu.title(), title);
             });
         });
     }
 
+    #[test]
+    fn test_from_raw() {
+        autoreleasepool(|_| {
+            let menu = NSMenu::new_with_title("external");
+            let ptr = &*menu as *const NSMenu as *mut Object;
+            // Pretend the menu is owned by someone else
+            mem::forget(menu);
+            let mut menu = unsafe { NSMenu::from_raw(ptr) }.unwrap();
+            let _: () = unsafe { msg_send![ptr, release] };
+            assert_eq!(menu.title(), "external");
+            menu.set_title("changed");
+            assert_eq!(menu.title(), "changed");
+
+            assert!(unsafe { NSMenu::from_raw(ptr::null_mut()) }.is_none());
+        });
+    }
+
+    #[test]
+    fn test_retain() {
+        autoreleasepool(|_| {
+            let menu = NSMenu::new_with_title("external");
+            let object = unsafe { &*(&*menu as *const NSMenu as *const Object) };
+            let retained = unsafe { NSMenu::retain(object) }.unwrap();
+            assert_eq!(&*retained, &*menu);
+
+            let item = NSMenuItem::new_empty();
+            let object = unsafe { &*(&*item as *const NSMenuItem as *const Object) };
+            assert!(unsafe { NSMenu::retain(object) }.is_none());
+        });
+    }
+
+    #[test]
+    fn test_move_item() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let first = menu.add_item("first");
+            let mut submenu = NSMenu::new();
+            let _ = submenu.add_item("nested");
+            let mut second = NSMenuItem::new("second", "", None);
+            let submenu = second.set_submenu(Some(submenu)).unwrap();
+            let _ = menu.add(second);
+            let _ = menu.add_item("third");
+
+            let titles = |menu: &NSMenu| -> Vec<String> {
+                (0..menu.len())
+                    .map(|i| menu.item_at(pool, i).unwrap().title(pool).to_owned())
+                    .collect()
+            };
+
+            menu.move_item(0, 2);
+            assert_eq!(titles(&menu), ["second", "third", "first"]);
+            menu.move_item(2, 1);
+            assert_eq!(titles(&menu), ["second", "first", "third"]);
+            menu.move_item(1, 1);
+            assert_eq!(titles(&menu), ["second", "first", "third"]);
+
+            assert_eq!(menu.item_at(pool, 1), Some(&*first));
+            let second = menu.item_at(pool, 0).unwrap();
+            assert_eq!(second.submenu(pool), Some(&*submenu));
+            assert_eq!(submenu.len(), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic = "Failed moving item: Index 3 out of bounds for number of items 3"]
+    fn test_move_item_out_of_bounds() {
+        let mut menu = NSMenu::new();
+        for _ in 0..3 {
+            let _ = menu.add(NSMenuItem::new_empty());
+        }
+        menu.move_item(0, 3);
+    }
+
+    #[test]
+    fn test_debug() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new_with_title("root");
+            let _ = menu.add_item("first");
+            let mut submenu = NSMenu::new_with_title("submenu");
+            let _ = submenu.add_item("nested");
+            let mut item = NSMenuItem::new("parent", "", None);
+            let _ = item.set_submenu(Some(submenu));
+            let _ = menu.add(item);
+
+            let debug = format!("{:?}", menu);
+            for title in &["root", "first", "parent", "submenu", "nested"] {
+                assert!(debug.contains(title), "{} not in {}", title, debug);
+            }
+        });
+    }
+
+    #[test]
+    fn test_debug_deeply_nested() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new_with_title("innermost");
+            for _ in 0..(MAX_DEBUG_DEPTH + 2) {
+                let mut item = NSMenuItem::new("item", "", None);
+                let _ = item.set_submenu(Some(menu));
+                menu = NSMenu::new();
+                let _ = menu.add(item);
+            }
+            let debug = format!("{:?}", menu);
+            assert!(debug.contains(".."));
+            assert!(!debug.contains("innermost"));
+            // The depth is reset afterwards
+            assert!(format!("{:?}", NSMenu::new_with_title("menu")).contains("items: []"));
+        });
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let first = NSMenu::new_with_title("menu");
+        let second = NSMenu::new_with_title("menu");
+        let menus: HashSet<&NSMenu> = [&*first, &*second, &*first].iter().copied().collect();
+        assert_eq!(menus.len(), 2);
+        assert_ne!(&*first, &*second);
+    }
+
     #[test]
     fn test_length() {
         autoreleasepool(|pool| {
             let mut menu = NSMenu::new();
             assert_eq!(menu.len(), 0);
+            assert!(menu.is_empty());
             menu.add(NSMenuItem::new_empty());
             assert_eq!(menu.len(), 1);
+            assert!(!menu.is_empty());
             menu.add(NSMenuItem::new_separator());
             assert_eq!(menu.len(), 2);
             menu.add(NSMenuItem::new("test", "", None));
             assert_eq!(menu.len(), 3);
             menu.insert(NSMenuItem::new("test", "", None), 2);
             assert_eq!(menu.len(), 4);
+            menu.add_separator();
+            menu.add_separator();
+            assert_eq!(menu.len(), 6);
             menu.remove_all();
             assert_eq!(menu.len(), 0);
         });
     }
 
+    #[test]
+    fn test_add_returns_item() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let item = menu.add(NSMenuItem::new("test", "", None));
+            let separator = menu.add_separator();
+            assert_eq!(menu.item_at(pool, 0), Some(&*item));
+            assert_eq!(menu.item_at(pool, 1), Some(&*separator));
+            assert!(separator.is_separator());
+        });
+    }
+
+    #[test]
+    fn test_add_item() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let item = menu.add_item("Plain");
+            assert_eq!(item.title(pool), "Plain");
+            assert_eq!(item.key_equivalent(pool), "");
+
+            let item = menu.add_item_with_key("Open", "o", KeyModifiers::COMMAND);
+            assert_eq!(item.title(pool), "Open");
+            assert_eq!(item.key_equivalent(pool), "o");
+            assert_eq!(item.key_equivalent_modifier_mask(), KeyModifiers::COMMAND);
+
+            let item = menu.add_item_with_action("Run", "", KeyModifiers::empty(), || {});
+            assert_eq!(item.title(pool), "Run");
+            assert!(item.is_enabled());
+
+            assert_eq!(menu.len(), 3);
+            assert_eq!(menu.item_at(pool, 2), Some(&*item));
+        });
+    }
+
+    #[test]
+    fn test_insert() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            menu.insert(NSMenuItem::new("b", "", None), 0);
+            menu.insert(NSMenuItem::new("a", "", None), 0);
+            menu.insert(NSMenuItem::new("d", "", None), 2);
+            menu.insert(NSMenuItem::new("c", "", None), 2);
+            let titles: Vec<_> = menu.iter(pool).map(|item| item.title(pool)).collect();
+            assert_eq!(titles, ["a", "b", "c", "d"]);
+        });
+    }
+
+    #[test]
+    #[should_panic = "Index 2 larger than number of items 1"]
+    fn test_insert_out_of_bounds() {
+        let mut menu = NSMenu::new();
+        menu.add(NSMenuItem::new_empty());
+        menu.insert(NSMenuItem::new_empty(), 2);
+    }
+
+    #[test]
+    fn test_duplicate() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        autoreleasepool(|pool| {
+            let count = Rc::new(Cell::new(0));
+            let mut menu = NSMenu::new_with_title("Menu");
+            let mut item = NSMenuItem::new("Open", "", None);
+            item.set_key_equivalent("O", KeyModifiers::COMMAND);
+            item.set_state(MenuItemState::On);
+            item.set_tag(3);
+            item.set_action({
+                let count = count.clone();
+                move || count.set(count.get() + 1)
+            });
+            menu.add(item);
+            menu.add_separator();
+            let mut submenu_item = NSMenuItem::new("Recent", "", None);
+            let mut submenu = NSMenu::new();
+            submenu.add(NSMenuItem::new("a.txt", "", None));
+            submenu_item.set_submenu(Some(submenu));
+            menu.add(submenu_item);
+            let font = NSFont::system_font_of_size(20.0);
+            menu.set_font(&font);
+            menu.set_minimum_width(250.0);
+            menu.set_shows_state_column(false);
+            menu.set_allows_context_menu_plugins(false);
+
+            let copy = menu.duplicate();
+            assert_ne!(*copy, *menu);
+            assert_eq!(copy.title(), "Menu");
+            assert_eq!(copy.len(), 3);
+            assert_eq!(copy.font(pool).point_size(), 20.0);
+            assert_eq!(copy.minimum_width(), 250.0);
+            assert!(!copy.shows_state_column());
+            assert!(!copy.allows_context_menu_plugins());
+
+            let item = copy.item_at(pool, 0).unwrap();
+            assert_eq!(item.title(pool), "Open");
+            assert_eq!(item.key_equivalent(pool), "o");
+            assert_eq!(
+                item.key_equivalent_modifier_mask(),
+                KeyModifiers::COMMAND | KeyModifiers::SHIFT
+            );
+            assert_eq!(item.state(), MenuItemState::On);
+            assert_eq!(item.tag(), 3);
+            assert!(copy.item_at(pool, 1).unwrap().is_separator());
+
+            let submenu = copy.item_at(pool, 2).unwrap().submenu(pool).unwrap();
+            let original = menu.item_at(pool, 2).unwrap().submenu(pool).unwrap();
+            assert_ne!(submenu, original);
+            assert_eq!(submenu.item_at(pool, 0).unwrap().title(pool), "a.txt");
+
+            // The action is shared
+            copy.perform_action_for_item_at(0);
+            menu.perform_action_for_item_at(0);
+            assert_eq!(count.get(), 2);
+        });
+    }
+
+    #[test]
+    fn test_insert_separator() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new("a", "", None));
+            menu.add(NSMenuItem::new("b", "", None));
+            let separator = menu.insert_separator(1);
+            assert!(separator.is_separator());
+            assert_eq!(menu.index_of_item(&separator), Some(1));
+            assert_eq!(menu.item_at(pool, 2).unwrap().title(pool), "b");
+        });
+    }
+
+    #[test]
+    #[should_panic = "Index 2 larger than number of items 1"]
+    fn test_insert_separator_out_of_bounds() {
+        let mut menu = NSMenu::new();
+        menu.add(NSMenuItem::new_empty());
+        menu.insert_separator(2);
+    }
+
+    #[test]
+    fn test_remove() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let a = menu.add(NSMenuItem::new("a", "", None));
+            let b = menu.add(NSMenuItem::new("b", "", None));
+            menu.add(NSMenuItem::new("c", "", None));
+
+            menu.remove(&b);
+            assert_eq!(menu.len(), 2);
+            // Removing an item that is not in the menu is a no-op
+            menu.remove(&b);
+            menu.remove(&NSMenuItem::new_empty());
+            assert_eq!(menu.len(), 2);
+
+            let removed = menu.remove_at(1);
+            assert_eq!(removed.title(pool), "c");
+            assert_eq!(menu.len(), 1);
+            assert_eq!(menu.iter(pool).next(), Some(&*a));
+        });
+    }
+
+    #[test]
+    #[should_panic = "Index 1 out of bounds for number of items 1"]
+    fn test_remove_at_out_of_bounds() {
+        let mut menu = NSMenu::new();
+        menu.add(NSMenuItem::new_empty());
+        menu.remove_at(1);
+    }
+
+    #[test]
+    fn test_add_all() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new("first", "", None));
+            menu.add_all((0..500).map(|i| NSMenuItem::new(&i.to_string(), "", None)));
+            assert_eq!(menu.len(), 501);
+            assert_eq!(menu.item_at(pool, 0).unwrap().title(pool), "first");
+            assert_eq!(menu.item_at(pool, 500).unwrap().title(pool), "499");
+
+            let enabled: Bool = unsafe { msg_send![&*menu, menuChangedMessagesEnabled] };
+            assert!(enabled.is_true());
+        });
+    }
+
+    #[test]
+    fn test_remove_all() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            menu.remove_all();
+            assert!(menu.is_empty());
+
+            menu.add(NSMenuItem::new_empty());
+            menu.add_separator();
+            let mut item = NSMenuItem::new("submenu", "", None);
+            let mut submenu = NSMenu::new();
+            submenu.add(NSMenuItem::new_empty());
+            item.set_submenu(Some(submenu));
+            menu.add(item);
+            assert_eq!(menu.len(), 3);
+
+            menu.remove_all();
+            assert_eq!(menu.len(), 0);
+            assert!(menu.iter(pool).next().is_none());
+        });
+    }
+
+    #[test]
+    fn test_item_at() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            assert!(menu.item_at(pool, 0).is_none());
+            let a = menu.add(NSMenuItem::new("a", "", None));
+            let b = menu.add(NSMenuItem::new("b", "", None));
+            assert_eq!(menu.item_at(pool, 0), Some(&*a));
+            assert_eq!(menu.item_at(pool, 1), Some(&*b));
+            assert!(menu.item_at(pool, 2).is_none());
+            assert!(menu.item_at(pool, usize::MAX).is_none());
+        });
+    }
+
+    #[test]
+    fn test_length_hidden() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            let mut item = NSMenuItem::new_empty();
+            item.set_hidden(true);
+            menu.add(item);
+            assert_eq!(menu.len(), 1);
+            assert!(!menu.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_find_by_tag() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            assert!(menu.item_with_tag(pool, 42).is_none());
+            assert_eq!(menu.index_of_item_with_tag(42), None);
+
+            menu.add(NSMenuItem::new_empty());
+            let mut item = NSMenuItem::new_empty();
+            item.set_tag(42);
+            let item = menu.add(item);
+            assert_eq!(menu.item_with_tag(pool, 42), Some(&*item));
+            assert_eq!(menu.index_of_item_with_tag(42), Some(1));
+            assert_eq!(menu.index_of_item_with_tag(-1), None);
+        });
+    }
+
+    #[test]
+    fn test_index_of_item() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            let first = menu.add(NSMenuItem::new_empty());
+            let second = menu.add(NSMenuItem::new_empty());
+            assert_eq!(menu.index_of_item(&first), Some(0));
+            assert_eq!(menu.index_of_item(&second), Some(1));
+            menu.remove(&first);
+            assert_eq!(menu.index_of_item(&first), None);
+            assert_eq!(menu.index_of_item(&second), Some(0));
+        });
+    }
+
+    #[test]
+    fn test_index_of_item_with_submenu() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new_empty());
+            let mut item = NSMenuItem::new_empty();
+            let submenu = item.set_submenu(Some(NSMenu::new())).unwrap();
+            assert_eq!(menu.index_of_item_with_submenu(&submenu), None);
+            menu.add(item);
+            assert_eq!(menu.index_of_item_with_submenu(&submenu), Some(1));
+        });
+    }
+
+    #[test]
+    fn test_index_of_item_with_represented_object() {
+        autoreleasepool(|_| {
+            let object = NSString::from_str("object");
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new_empty());
+            assert_eq!(menu.index_of_item_with_represented_object(&object), None);
+            let mut item = NSMenuItem::new_empty();
+            item.set_represented_object(Some(&object));
+            menu.add(item);
+            assert_eq!(menu.index_of_item_with_represented_object(&object), Some(1));
+        });
+    }
+
+    #[test]
+    fn test_index_of_item_with_target_and_action() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new_empty());
+            let mut item = NSMenuItem::new_empty();
+            item.set_action(|| {});
+            let item = menu.add(item);
+
+            let target: *mut Object = unsafe { msg_send![&*item, target] };
+            let target = unsafe { target.as_ref() };
+            let action: Sel = unsafe { msg_send![&*item, action] };
+            assert_eq!(
+                menu.index_of_item_with_target_and_action(target, Some(action)),
+                Some(1)
+            );
+            assert_eq!(
+                menu.index_of_item_with_target_and_action(target, None),
+                Some(1)
+            );
+            assert_eq!(
+                menu.index_of_item_with_target_and_action(target, Some(sel!(copy:))),
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn test_set_submenu_for_item() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let item = menu.add(NSMenuItem::new_empty());
+            let submenu = NSMenu::new();
+            menu.set_submenu_for_item(&submenu, &item);
+            assert_eq!(item.submenu(pool), Some(&*submenu));
+            assert_eq!(submenu.supermenu(pool), Some(&*menu));
+        });
+    }
+
+    #[test]
+    #[should_panic = "The menu is already a submenu in another menu"]
+    fn test_set_submenu_for_item_other_menu() {
+        let mut menu = NSMenu::new();
+        let item = menu.add(NSMenuItem::new_empty());
+        let mut other = NSMenu::new();
+        let mut parent = NSMenuItem::new_empty();
+        let submenu = parent.set_submenu(Some(NSMenu::new())).unwrap();
+        other.add(parent);
+        menu.set_submenu_for_item(&submenu, &item);
+    }
+
+    #[test]
+    fn test_find_by_title() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            assert!(menu.item_with_title(pool, "Quit").is_none());
+            assert_eq!(menu.index_of_item_with_title("Quit"), None);
+
+            menu.add(NSMenuItem::new("About", "", None));
+            let item = menu.add(NSMenuItem::new("Quit", "", None));
+            assert_eq!(menu.item_with_title(pool, "Quit"), Some(&*item));
+            assert_eq!(menu.index_of_item_with_title("Quit"), Some(1));
+
+            // Exact and case-sensitive
+            assert!(menu.item_with_title(pool, "quit").is_none());
+            assert!(menu.item_with_title(pool, "Qui").is_none());
+            assert_eq!(menu.index_of_item_with_title("QUIT"), None);
+        });
+    }
+
+    #[test]
+    fn test_autoenables_items() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            assert!(menu.autoenables_items());
+            menu.set_autoenables_items(false);
+            assert!(!menu.autoenables_items());
+
+            let mut item = NSMenuItem::new("item", "", None);
+            item.set_enabled(false);
+            let item = menu.add(item);
+            menu.update();
+            assert!(!item.is_enabled());
+
+            menu.set_autoenables_items(true);
+            assert!(menu.autoenables_items());
+        });
+    }
+
+    #[test]
+    fn test_update() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            // Items without an action are disabled by validation
+            let no_action = menu.add(NSMenuItem::new("no action", "", None));
+            let mut item = NSMenuItem::new("action", "", None);
+            item.set_action(|| {});
+            let action = menu.add(item);
+            assert!(no_action.is_enabled());
+
+            menu.update();
+            assert!(!no_action.is_enabled());
+            assert!(action.is_enabled());
+        });
+    }
+
+    #[test]
+    fn test_shows_state_column() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            assert!(menu.shows_state_column());
+            menu.set_shows_state_column(false);
+            assert!(!menu.shows_state_column());
+            menu.set_shows_state_column(true);
+            assert!(menu.shows_state_column());
+        });
+    }
+
+    #[test]
+    fn test_allows_context_menu_plugins() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            assert!(menu.allows_context_menu_plugins());
+            menu.set_allows_context_menu_plugins(false);
+            assert!(!menu.allows_context_menu_plugins());
+            menu.set_allows_context_menu_plugins(true);
+            assert!(menu.allows_context_menu_plugins());
+        });
+    }
+
+    #[test]
+    fn test_minimum_width() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            assert_eq!(menu.minimum_width(), 0.0);
+            menu.set_minimum_width(200.0);
+            assert_eq!(menu.minimum_width(), 200.0);
+            menu.set_minimum_width(0.0);
+            assert_eq!(menu.minimum_width(), 0.0);
+        });
+    }
+
+    #[test]
+    fn test_font() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let font = NSFont::menu_font_of_size(24.0);
+            menu.set_font(&font);
+            assert_eq!(menu.font(pool).point_size(), 24.0);
+        });
+    }
+
+    #[test]
+    fn test_appearance() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let name = |menu: &NSMenu| {
+                let appearance: *mut Object = unsafe { msg_send![menu, appearance] };
+                unsafe { appearance.as_ref() }.map(|appearance| {
+                    let name: &NSString = unsafe { msg_send![appearance, name] };
+                    name.as_str(pool).to_owned()
+                })
+            };
+            assert_eq!(name(&menu), None);
+            menu.set_appearance(Appearance::DarkAqua);
+            assert_eq!(name(&menu).as_deref(), Some("NSAppearanceNameDarkAqua"));
+            menu.set_appearance(Appearance::Aqua);
+            assert_eq!(name(&menu).as_deref(), Some("NSAppearanceNameAqua"));
+            menu.set_appearance(Appearance::System);
+            assert_eq!(name(&menu), None);
+        });
+    }
+
+    #[test]
+    fn test_delegate() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        autoreleasepool(|_| {
+            let opened = Rc::new(Cell::new(0));
+            let closed = Rc::new(Cell::new(0));
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new_empty());
+            menu.set_delegate(
+                MenuDelegate::new()
+                    .on_open({
+                        let opened = opened.clone();
+                        move |menu| {
+                            assert_eq!(menu.len(), 1);
+                            opened.set(opened.get() + 1);
+                        }
+                    })
+                    .on_close({
+                        let closed = closed.clone();
+                        move |_| closed.set(closed.get() + 1)
+                    }),
+            );
+
+            let delegate: *mut Object = unsafe { msg_send![&*menu, delegate] };
+            let _: () = unsafe { msg_send![delegate, menuWillOpen: &*menu] };
+            assert_eq!((opened.get(), closed.get()), (1, 0));
+            assert_eq!(menu.len(), 1);
+            let _: () = unsafe { msg_send![delegate, menuDidClose: &*menu] };
+            assert_eq!((opened.get(), closed.get()), (1, 1));
+
+            // Replacing the delegate releases the old callbacks
+            menu.set_delegate(MenuDelegate::new());
+            assert_eq!(Rc::strong_count(&opened), 1);
+            assert_eq!(Rc::strong_count(&closed), 1);
+        });
+    }
+
+    #[test]
+    fn test_delegate_observers() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        autoreleasepool(|_| {
+            let events = Rc::new(RefCell::new(Vec::new()));
+            let observer = |name: &'static str| {
+                let events = events.clone();
+                MenuObserver::new()
+                    .on_open({
+                        let events = events.clone();
+                        move |_| events.borrow_mut().push((name, "open"))
+                    })
+                    .on_close(move |_| events.borrow_mut().push((name, "close")))
+            };
+            let mut menu = NSMenu::new();
+            menu.add_delegate_observer(observer("first"));
+            menu.add_delegate_observer(observer("second"));
+            // Replacing the delegate keeps the observers
+            menu.set_delegate(MenuDelegate::new().on_open({
+                let events = events.clone();
+                move |_| events.borrow_mut().push(("delegate", "open"))
+            }));
+
+            let delegate: *mut Object = unsafe { msg_send![&*menu, delegate] };
+            let _: () = unsafe { msg_send![delegate, menuWillOpen: &*menu] };
+            let _: () = unsafe { msg_send![delegate, menuDidClose: &*menu] };
+            assert_eq!(
+                *events.borrow(),
+                [
+                    ("delegate", "open"),
+                    ("first", "open"),
+                    ("second", "open"),
+                    ("first", "close"),
+                    ("second", "close"),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_lazy_populator() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        autoreleasepool(|pool| {
+            let opened = Rc::new(Cell::new(0));
+            let clicked = Rc::new(Cell::new(0));
+            let mut menu = NSMenu::new();
+            menu.set_delegate(MenuDelegate::new().on_open({
+                let opened = opened.clone();
+                move |_| opened.set(opened.get() + 1)
+            }));
+            menu.set_lazy_populator(|| 1000, {
+                let clicked = clicked.clone();
+                move |_, item, index| {
+                    item.set_title(&format!("Item {}", index));
+                    let clicked = clicked.clone();
+                    item.set_action(move || clicked.set(clicked.get() + 1));
+                    index < 10
+                }
+            });
+
+            let delegate: *mut Object = unsafe { msg_send![&*menu, delegate] };
+            let count: NSInteger = unsafe { msg_send![delegate, numberOfItemsInMenu: &*menu] };
+            assert_eq!(count, 1000);
+
+            let item = NSMenuItem::new_empty();
+            let should_continue: Bool = unsafe {
+                msg_send![
+                    delegate,
+                    menu: &*menu,
+                    updateItem: &*item,
+                    atIndex: 5 as NSInteger,
+                    shouldCancel: Bool::NO,
+                ]
+            };
+            assert!(should_continue.is_true());
+            assert_eq!(item.title(pool), "Item 5");
+            // The action's target is moved over, and kept alive
+            let target: *const Object = unsafe { msg_send![&*item, target] };
+            assert!(!target.is_null());
+            let action: Sel = unsafe { msg_send![&*item, action] };
+            let _: () = unsafe { msg_send![target, performSelector: action, withObject: &*item] };
+            assert_eq!(clicked.get(), 1);
+
+            let should_continue: Bool = unsafe {
+                msg_send![
+                    delegate,
+                    menu: &*menu,
+                    updateItem: &*item,
+                    atIndex: 42 as NSInteger,
+                    shouldCancel: Bool::NO,
+                ]
+            };
+            assert!(!should_continue.is_true());
+
+            // The existing callbacks are kept
+            let _: () = unsafe { msg_send![delegate, menuWillOpen: &*menu] };
+            assert_eq!(opened.get(), 1);
+        });
+    }
+
+    #[test]
+    fn test_highlight_handler() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        autoreleasepool(|pool| {
+            let highlighted = Rc::new(RefCell::new(Vec::new()));
+            let mut menu = NSMenu::new();
+            let item = menu.add(NSMenuItem::new("item", "", None));
+            menu.set_highlight_handler({
+                let highlighted = highlighted.clone();
+                move |item| {
+                    let title = autoreleasepool(|pool| item.map(|i| i.title(pool).to_owned()));
+                    highlighted.borrow_mut().push(title);
+                }
+            });
+
+            let delegate: *mut Object = unsafe { msg_send![&*menu, delegate] };
+            let _: () = unsafe { msg_send![delegate, menu: &*menu, willHighlightItem: &*item] };
+            let none: *const NSMenuItem = core::ptr::null();
+            let _: () = unsafe { msg_send![delegate, menu: &*menu, willHighlightItem: none] };
+            assert_eq!(*highlighted.borrow(), [Some("item".to_owned()), None]);
+        });
+    }
+
+    #[test]
+    fn test_confinement_handler() {
+        use objc2_foundation::NSRect;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        autoreleasepool(|_| {
+            let confine = Rc::new(Cell::new(false));
+            let mut menu = NSMenu::new();
+            menu.set_confinement_handler({
+                let confine = confine.clone();
+                move || confine.get().then_some((10.0, 20.0, 300.0, 400.0))
+            });
+
+            let delegate: *mut Object = unsafe { msg_send![&*menu, delegate] };
+            let screen: *const Object = core::ptr::null();
+            let rect = || -> NSRect {
+                unsafe { msg_send![delegate, confinementRectForMenu: &*menu, onScreen: screen] }
+            };
+            assert_eq!(rect(), NSRect::ZERO);
+            confine.set(true);
+            let rect = rect();
+            assert_eq!(rect.origin, NSPoint::new(10.0, 20.0));
+            assert_eq!(rect.size, NSSize::new(300.0, 400.0));
+        });
+    }
+
+    #[test]
+    fn test_properties_to_update() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        autoreleasepool(|_| {
+            let seen = Rc::new(Cell::new(None));
+            let mut menu = NSMenu::new();
+            menu.set_lazy_populator(|| 1, {
+                let seen = seen.clone();
+                move |menu, _, _| {
+                    seen.set(Some((menu as *const NSMenu, menu.properties_to_update())));
+                    true
+                }
+            });
+
+            let delegate: *mut Object = unsafe { msg_send![&*menu, delegate] };
+            let item = NSMenuItem::new_empty();
+            let _: Bool = unsafe {
+                msg_send![
+                    delegate,
+                    menu: &*menu,
+                    updateItem: &*item,
+                    atIndex: 0 as NSInteger,
+                    shouldCancel: Bool::NO,
+                ]
+            };
+            // The callback is given the menu being updated
+            let (seen_menu, properties) = seen.get().unwrap();
+            assert_eq!(seen_menu, &*menu as *const NSMenu);
+            assert_eq!(properties, menu.properties_to_update());
+        });
+    }
+
+    #[test]
+    fn test_no_lazy_populator() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            menu.set_delegate(MenuDelegate::new());
+            let delegate: *mut Object = unsafe { msg_send![&*menu, delegate] };
+            let count: NSInteger = unsafe { msg_send![delegate, numberOfItemsInMenu: &*menu] };
+            assert!(count < 0);
+        });
+    }
+
+    #[test]
+    fn test_supermenu() {
+        autoreleasepool(|pool| {
+            let mut root = NSMenu::new();
+            assert!(root.supermenu(pool).is_none());
+            let mut item = NSMenuItem::new("parent", "", None);
+            let submenu = item.set_submenu(Some(NSMenu::new())).unwrap();
+            // Not yet in a menu
+            assert!(submenu.supermenu(pool).is_none());
+            root.add(item);
+            assert_eq!(submenu.supermenu(pool), Some(&*root));
+        });
+    }
+
+    #[test]
+    fn test_highlighted_item() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            assert!(menu.highlighted_item(pool).is_none());
+            menu.add(NSMenuItem::new_empty());
+            // Not open, so nothing can be highlighted
+            assert!(menu.highlighted_item(pool).is_none());
+        });
+    }
+
+    #[test]
+    fn test_size() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new("item", "", None));
+            let (width, height) = menu.size();
+            assert!(width > 0.0);
+            assert!(height > 0.0);
+
+            menu.add(NSMenuItem::new("a much longer item title", "", None));
+            let (longer_width, taller_height) = menu.size();
+            assert!(longer_width > width);
+            assert!(taller_height > height);
+        });
+    }
+
+    #[test]
+    fn test_iter_order() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new("b", "", None));
+            menu.add(NSMenuItem::new("c", "", None));
+            menu.insert(NSMenuItem::new("a", "", None), 0);
+            let titles: Vec<_> = menu.iter(pool).map(|item| item.title(pool)).collect();
+            assert_eq!(titles, ["a", "b", "c"]);
+        });
+    }
+
+    #[test]
+    fn test_perform_action_for_item_at() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        autoreleasepool(|_| {
+            let count = Rc::new(Cell::new(0));
+            let item_count = count.clone();
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new_empty());
+            let mut item = NSMenuItem::new("item", "", None);
+            item.set_action(move || item_count.set(item_count.get() + 1));
+            menu.add(item);
+
+            menu.perform_action_for_item_at(1);
+            assert_eq!(count.get(), 1);
+            // The first item has no action
+            menu.perform_action_for_item_at(0);
+            assert_eq!(count.get(), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic = "Index 1 out of bounds for number of items 1"]
+    fn test_perform_action_for_item_at_out_of_bounds() {
+        let mut menu = NSMenu::new();
+        menu.add(NSMenuItem::new_empty());
+        menu.perform_action_for_item_at(1);
+    }
+
+    #[test]
+    fn test_cancel_tracking_not_open() {
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            menu.add(NSMenuItem::new_empty());
+            menu.cancel_tracking();
+            menu.cancel_tracking_without_animation();
+            assert_eq!(menu.len(), 1);
+        });
+    }
+
     #[test]
     fn test_iter() {
         autoreleasepool(|pool| {
@@ -488,9 +2032,9 @@ mod tests {
             menu.add(NSMenuItem::new_separator());
             let mut iter = menu.iter(pool);
             assert_eq!(iter.size_hint(), (3, Some(3)));
-            assert!(!iter.next().unwrap().separator());
-            assert!(!iter.next().unwrap().separator());
-            assert!(iter.next().unwrap().separator());
+            assert!(!iter.next().unwrap().is_separator());
+            assert!(!iter.next().unwrap().is_separator());
+            assert!(iter.next().unwrap().is_separator());
             assert!(iter.next().is_none());
 
             // Modifying after creating the iterator (the iterator is unaffected)
@@ -498,15 +2042,15 @@ mod tests {
 
             menu.add(NSMenuItem::new_empty());
             assert_eq!(iter.size_hint(), (3, Some(3)));
-            assert!(!iter.next().unwrap().separator());
+            assert!(!iter.next().unwrap().is_separator());
 
             menu.add(NSMenuItem::new_separator());
             assert_eq!(iter.size_hint(), (3, Some(3)));
-            assert!(!iter.next().unwrap().separator());
+            assert!(!iter.next().unwrap().is_separator());
 
             menu.remove_all();
             assert_eq!(iter.size_hint(), (3, Some(3)));
-            assert!(iter.next().unwrap().separator());
+            assert!(iter.next().unwrap().is_separator());
 
             menu.add(NSMenuItem::new_separator());
             assert_eq!(iter.size_hint(), (3, Some(3)));
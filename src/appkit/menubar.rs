@@ -1,10 +1,18 @@
+use core::fmt;
+use core::ptr::NonNull;
+use std::collections::HashMap;
+
+use super::global::InitializedApplication;
+use super::keyequivalent::{self, KeyModifiers};
 use super::menu::NSMenu;
 use super::menuitem::NSMenuItem;
-use objc2::rc::{Id, Owned, Shared};
-use objc2::{class, msg_send, sel};
+use objc2::ffi::NSInteger;
+use objc2::rc::{autoreleasepool, AutoreleasePool, Id, Owned, Shared};
+use objc2::runtime::{Object, Sel};
+use objc2::{msg_send, sel};
+use objc2_foundation::NSString;
 
 /// Helper to make constructing the menu bar easier
-#[derive(Debug)]
 pub struct MenuBar(Id<NSMenu, Owned>);
 
 impl MenuBar {
@@ -28,12 +36,26 @@ impl MenuBar {
         menubar
     }
 
+    /// The menu that holds the top-level menus as the submenus of its
+    /// items, for using the full [`NSMenu`] API on the menu bar itself.
+    ///
+    /// The title of the root menu is ignored by AppKit, as are the titles of
+    /// its items; the menu bar displays the titles of the submenus instead,
+    /// see [`title`](Self::title).
+    pub fn root(&self) -> &NSMenu {
+        &self.0
+    }
+
+    /// Mutable access to the root menu, e.g. to reorder the top-level
+    /// menus, see [`root`](Self::root).
+    ///
+    /// The first item's submenu is used as the application menu.
+    pub fn root_mut(&mut self) -> &mut NSMenu {
+        &mut self.0
+    }
+
     fn add_menu(&mut self, menu: Id<NSMenu, Owned>) -> Id<NSMenu, Shared> {
-        // All parameters on menu items irrelevant in the menu bar
-        let mut item = NSMenuItem::new_empty();
-        let menu = item.set_submenu(Some(menu)).unwrap();
-        let _item = self.0.add(item);
-        menu
+        insert_menu(&self.0, self.0.len(), menu)
     }
 
     pub fn add(&mut self, title: &str, f: impl FnOnce(&mut NSMenu)) -> Id<NSMenu, Shared> {
@@ -42,23 +64,626 @@ impl MenuBar {
         self.add_menu(menu)
     }
 
+    /// Insert a new top-level menu at the given index, e.g. to place a
+    /// custom menu between the File and Edit menus.
+    ///
+    /// Index 0 is the application menu.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is larger than the number of menus.
+    pub fn insert(
+        &mut self,
+        index: usize,
+        title: &str,
+        f: impl FnOnce(&mut NSMenu),
+    ) -> Id<NSMenu, Shared> {
+        let mut menu = NSMenu::new_with_title(title);
+        f(&mut menu);
+        insert_menu(&self.0, index, menu)
+    }
+
+    /// Add the standard items to the application menu (the first menu).
+    ///
+    /// These are About, Preferences, Services, Hide, Hide Others, Show All and
+    /// Quit, with the conventional key equivalents, and `app_name` used in
+    /// the titles where appropriate. The items are appended after any items
+    /// already in the application menu.
+    ///
+    /// The Preferences item has no action; find it with
+    /// [`NSMenu::item_with_title`] to give it one, or it will be disabled.
+    /// The Services item's submenu should be passed to
+    /// [`InitializedApplication::set_services_menu`].
+    ///
+    /// [`InitializedApplication::set_services_menu`]: super::InitializedApplication::set_services_menu
+    pub fn add_standard_app_menu(&mut self, app_name: &str) -> Id<NSMenu, Shared> {
+        let menu = submenu_at(&self.0, 0);
+        // Handles to the application menu may exist (e.g. from a previous
+        // call), so the items are added through a shared reference
+        let menu_ref = match unsafe { menu.as_ref() } {
+            Some(menu) => menu,
+            None => panic!("Failed adding standard items: The first item has no menu"),
+        };
+        add_shared(
+            menu_ref,
+            standard_item(
+                &format!("About {}", app_name),
+                "",
+                sel!(orderFrontStandardAboutPanel:),
+            ),
+        );
+        add_shared(menu_ref, NSMenuItem::new_separator());
+        let mut preferences = NSMenuItem::new("Preferences…", "", None);
+        preferences.set_key_equivalent(",", KeyModifiers::COMMAND);
+        add_shared(menu_ref, preferences);
+        add_shared(menu_ref, NSMenuItem::new_separator());
+        let mut services = NSMenuItem::new("Services", "", None);
+        let _ = services.set_submenu(Some(NSMenu::new_with_title("Services")));
+        add_shared(menu_ref, services);
+        add_shared(menu_ref, NSMenuItem::new_separator());
+        add_shared(
+            menu_ref,
+            standard_item(&format!("Hide {}", app_name), "h", sel!(hide:)),
+        );
+        let mut hide_others = standard_item("Hide Others", "", sel!(hideOtherApplications:));
+        hide_others.set_key_equivalent("h", KeyModifiers::COMMAND | KeyModifiers::OPTION);
+        add_shared(menu_ref, hide_others);
+        add_shared(
+            menu_ref,
+            standard_item("Show All", "", sel!(unhideAllApplications:)),
+        );
+        add_shared(menu_ref, NSMenuItem::new_separator());
+        add_shared(
+            menu_ref,
+            standard_item(&format!("Quit {}", app_name), "q", sel!(terminate:)),
+        );
+        unsafe { Id::retain(menu).unwrap() }
+    }
+
+    /// Add the standard Edit menu, with Undo, Redo, Cut, Copy, Paste and
+    /// Select All items.
+    ///
+    /// The items use the conventional key equivalents (Redo uses Shift+Cmd+Z)
+    /// and the standard actions like `copy:`, with no target, so that they
+    /// are sent through the responder chain to e.g. the focused text field.
+    pub fn add_standard_edit_menu(&mut self) -> Id<NSMenu, Shared> {
+        self.add("Edit", |menu| {
+            menu.add(standard_item("Undo", "z", sel!(undo:)));
+            menu.add(standard_item("Redo", "Z", sel!(redo:)));
+            menu.add(NSMenuItem::new_separator());
+            menu.add(standard_item("Cut", "x", sel!(cut:)));
+            menu.add(standard_item("Copy", "c", sel!(copy:)));
+            menu.add(standard_item("Paste", "v", sel!(paste:)));
+            menu.add(standard_item("Select All", "a", sel!(selectAll:)));
+        })
+    }
+
+    /// Remove the top-level menu at the given index, returning its submenu so
+    /// that it can be reused.
+    ///
+    /// Useful for stripping menus that AppKit injects, like the Help menu.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<Id<NSMenu, Shared>> {
+        remove_menu(&self.0, index)
+    }
+
+    /// Whether the menu bar is visible for the entire application.
+    ///
+    /// Same as [`InitializedApplication::menubar_visible`].
+    ///
+    /// [`InitializedApplication::menubar_visible`]: super::InitializedApplication::menubar_visible
     #[doc(alias = "menuBarVisible")]
-    fn global_visible() -> bool {
-        unimplemented!()
+    pub fn global_visible(app: &InitializedApplication) -> bool {
+        app.menubar_visible()
     }
 
+    /// Hide or show the menu bar for the entire application.
+    ///
+    /// Same as [`InitializedApplication::set_menubar_visible`].
+    ///
+    /// [`InitializedApplication::set_menubar_visible`]: super::InitializedApplication::set_menubar_visible
     #[doc(alias = "setMenuBarVisible")]
     #[doc(alias = "setMenuBarVisible:")]
-    fn set_global_visible(visible: bool) {
-        unimplemented!()
+    pub fn set_global_visible(app: &InitializedApplication, visible: bool) {
+        app.set_menubar_visible(visible)
     }
 
+    /// The height of the application's menu bar, or `0.0` if no menu bar
+    /// has been set.
+    ///
+    /// Same as [`InitializedApplication::menubar_height`].
+    ///
+    /// [`InitializedApplication::menubar_height`]: super::InitializedApplication::menubar_height
     #[doc(alias = "menuBarHeight")]
-    fn global_height() -> f64 {
-        unimplemented!()
+    pub fn global_height(app: &InitializedApplication) -> f64 {
+        app.menubar_height()
+    }
+
+    /// The title of the top-level menu at the given index.
+    ///
+    /// The menu bar displays the title of each item's submenu, not the title
+    /// of the item itself, so this is what is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn title(&self, index: usize) -> String {
+        title_at(&self.0, index)
+    }
+
+    /// Find key equivalents that are assigned to more than one item, which
+    /// is usually a mistake, since only one of the items can be triggered
+    /// with it.
+    ///
+    /// All menus are searched, including submenus. Items that are disabled,
+    /// or hidden (unless their key equivalent [still works when
+    /// hidden](NSMenuItem::set_allows_key_equivalent_when_hidden)), are
+    /// ignored. Each conflicting key equivalent is reported once, in the
+    /// order they appear in the menus.
+    pub fn find_key_equivalent_conflicts(&self) -> Vec<(String, KeyModifiers)> {
+        fn visit(
+            menu: &NSMenu,
+            pool: &AutoreleasePool,
+            seen: &mut HashMap<(String, KeyModifiers), usize>,
+            order: &mut Vec<(String, KeyModifiers)>,
+        ) {
+            for item in menu.iter(pool) {
+                if let Some(submenu) = item.submenu(pool) {
+                    visit(submenu, pool, seen, order);
+                }
+                let key = item.key_equivalent(pool);
+                if key.is_empty() || !item.is_enabled() {
+                    continue;
+                }
+                if item.is_hidden() && !item.allows_key_equivalent_when_hidden() {
+                    continue;
+                }
+                let (key, modifiers) = keyequivalent::normalize_key_equivalent(
+                    key,
+                    item.key_equivalent_modifier_mask(),
+                );
+                let count = seen.entry((key.clone(), modifiers)).or_insert(0);
+                *count += 1;
+                if *count == 2 {
+                    order.push((key, modifiers));
+                }
+            }
+        }
+
+        let mut seen = HashMap::new();
+        let mut conflicts = Vec::new();
+        autoreleasepool(|pool| visit(&self.0, pool, &mut seen, &mut conflicts));
+        conflicts
+    }
+
+    /// The titles of all top-level menus, in order, see [`title`].
+    ///
+    /// The first title is that of the application menu, which is usually
+    /// empty, since AppKit displays the application's name instead.
+    ///
+    /// [`title`]: Self::title
+    pub fn titles(&self) -> Vec<String> {
+        titles(&self.0)
+    }
+
+    /// Rename the top-level menu at the given index.
+    ///
+    /// This sets the title of the item's submenu, since that is what the menu
+    /// bar displays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn set_title(&mut self, index: usize, title: &str) {
+        set_title_at(&self.0, index, title)
     }
+}
+
+impl fmt::Debug for MenuBar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_menus(&self.0, "MenuBar", f)
+    }
+}
 
-    // How do we handle this???
-    // pub fn title(index) {}
-    // pub fn set_title(index, title) {}
+/// A handle to the installed menu bar, returned from
+/// [`InitializedApplication::set_menubar`].
+///
+/// The [`MenuBar`] builder is consumed when the menu bar is set, so this
+/// exposes the operations that remain useful on the live menu bar, like
+/// adding, removing and renaming top-level menus, e.g. when a document is
+/// opened. Changes are visible immediately.
+///
+/// The root menu is shared with the application (see
+/// [`InitializedApplication::menubar`]), so unlike [`MenuBar`] this gives
+/// no mutable access to it.
+///
+/// Dropping the handle does not remove the menu bar; the application keeps
+/// it alive for as long as it is installed. If the menu bar is replaced, the
+/// handle keeps referring to the previous menu bar.
+///
+/// [`InitializedApplication::set_menubar`]: super::InitializedApplication::set_menubar
+/// [`InitializedApplication::menubar`]: super::InitializedApplication::menubar
+pub struct MainMenu(Id<NSMenu, Shared>);
+
+impl MainMenu {
+    pub(super) fn new(root: Id<NSMenu, Shared>) -> Self {
+        Self(root)
+    }
+
+    /// The root menu, see [`MenuBar::root`].
+    pub fn root(&self) -> &NSMenu {
+        &self.0
+    }
+
+    /// Add a new top-level menu after the existing ones, see
+    /// [`MenuBar::add`].
+    pub fn add(&mut self, title: &str, f: impl FnOnce(&mut NSMenu)) -> Id<NSMenu, Shared> {
+        let mut menu = NSMenu::new_with_title(title);
+        f(&mut menu);
+        insert_menu(&self.0, self.0.len(), menu)
+    }
+
+    /// Insert a new top-level menu at the given index, see
+    /// [`MenuBar::insert`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is larger than the number of menus.
+    pub fn insert(
+        &mut self,
+        index: usize,
+        title: &str,
+        f: impl FnOnce(&mut NSMenu),
+    ) -> Id<NSMenu, Shared> {
+        let mut menu = NSMenu::new_with_title(title);
+        f(&mut menu);
+        insert_menu(&self.0, index, menu)
+    }
+
+    /// Remove the top-level menu at the given index, see
+    /// [`MenuBar::remove`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<Id<NSMenu, Shared>> {
+        remove_menu(&self.0, index)
+    }
+
+    /// The top-level menu at the given index, e.g. to enable or disable its
+    /// items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn menu(&self, index: usize) -> Option<Id<NSMenu, Shared>> {
+        let menu = submenu_at(&self.0, index);
+        unsafe { Id::retain(menu) }
+    }
+
+    /// The title of the top-level menu at the given index, see
+    /// [`MenuBar::title`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn title(&self, index: usize) -> String {
+        title_at(&self.0, index)
+    }
+
+    /// The titles of all top-level menus, see [`MenuBar::titles`].
+    pub fn titles(&self) -> Vec<String> {
+        titles(&self.0)
+    }
+
+    /// Rename the top-level menu at the given index, see
+    /// [`MenuBar::set_title`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn set_title(&mut self, index: usize, title: &str) {
+        set_title_at(&self.0, index, title)
+    }
+}
+
+impl fmt::Debug for MainMenu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_menus(&self.0, "MainMenu", f)
+    }
+}
+
+// The operations on the top-level menus below are shared by `MenuBar` and
+// `MainMenu`. They only take a shared reference to the root menu, since the
+// root of the installed menu bar is also handed out by
+// `InitializedApplication::menubar`, and the menus by e.g. `MenuBar::add`.
+
+fn submenu_at(root: &NSMenu, index: usize) -> *mut NSMenu {
+    let length = root.len();
+    if index >= length {
+        panic!(
+            "Failed accessing menu: Index {} out of bounds for number of menus {}",
+            index, length
+        );
+    }
+    let item: *mut NSMenuItem = unsafe { msg_send![root, itemAtIndex: index as NSInteger] };
+    unsafe { msg_send![item, submenu] }
+}
+
+fn insert_menu(root: &NSMenu, index: usize, menu: Id<NSMenu, Owned>) -> Id<NSMenu, Shared> {
+    let length = root.len();
+    if index > length {
+        panic!(
+            "Failed inserting item: Index {} larger than number of items {}",
+            index, length
+        );
+    }
+    // All parameters on menu items irrelevant in the menu bar
+    let mut item = NSMenuItem::new_empty();
+    let menu = item.set_submenu(Some(menu)).unwrap();
+    let _: () = unsafe { msg_send![root, insertItem: &*item, atIndex: index as NSInteger] };
+    menu
+}
+
+fn remove_menu(root: &NSMenu, index: usize) -> Option<Id<NSMenu, Shared>> {
+    let length = root.len();
+    if index >= length {
+        panic!(
+            "Failed removing item: Index {} out of bounds for number of items {}",
+            index, length
+        );
+    }
+    let index = index as NSInteger;
+    let item: *mut NSMenuItem = unsafe { msg_send![root, itemAtIndex: index] };
+    // Retain the submenu before removing the item, which releases it
+    let menu: *mut NSMenu = unsafe { msg_send![item, submenu] };
+    let menu = unsafe { Id::retain(menu) };
+    let _: () = unsafe { msg_send![root, removeItemAtIndex: index] };
+    menu
+}
+
+fn title_at(root: &NSMenu, index: usize) -> String {
+    let menu = submenu_at(root, index);
+    unsafe { menu.as_ref() }
+        .map(NSMenu::title)
+        .unwrap_or_default()
+}
+
+fn titles(root: &NSMenu) -> Vec<String> {
+    (0..root.len()).map(|index| title_at(root, index)).collect()
+}
+
+fn set_title_at(root: &NSMenu, index: usize, title: &str) {
+    let menu = submenu_at(root, index);
+    if let Some(menu) = unsafe { menu.as_ref() } {
+        let title = NSString::from_str(title);
+        let _: () = unsafe { msg_send![menu, setTitle: &*title] };
+    }
+}
+
+fn debug_menus(root: &NSMenu, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // The items of the root menu are only placeholders for the menus
+    let menus: Vec<Option<&NSMenu>> = (0..root.len())
+        .map(|index| unsafe { submenu_at(root, index).as_ref() })
+        .collect();
+    f.debug_struct(name)
+        .field("id", &(root as *const NSMenu))
+        .field("menus", &menus)
+        .finish()
+}
+
+/// Append `item` to `menu` without creating a `&mut` to the menu, for
+/// menus that shared handles may exist to.
+fn add_shared(menu: &NSMenu, item: Id<NSMenuItem, Owned>) {
+    let _: () = unsafe { msg_send![menu, addItem: &*item] };
+}
+
+/// Create an item with a nil target, sending `action` through the
+/// responder chain.
+fn standard_item(title: &str, key: &str, action: Sel) -> Id<NSMenuItem, Owned> {
+    let mut item = NSMenuItem::new(title, "", NonNull::new(action.as_ptr() as *mut _));
+    if !key.is_empty() {
+        item.set_key_equivalent(key, KeyModifiers::COMMAND);
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2::rc::autoreleasepool;
+
+    #[test]
+    fn test_remove() {
+        let mut menubar = MenuBar::new(|_| {});
+        let file = menubar.add("File", |_| {});
+        let _help = menubar.add("Help", |_| {});
+        assert_eq!(menubar.0.len(), 3);
+
+        let removed = menubar.remove(1).unwrap();
+        assert_eq!(removed, file);
+        assert_eq!(removed.title(), "File");
+        assert_eq!(menubar.0.len(), 2);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut menubar = MenuBar::new(|_| {});
+        let _file = menubar.add("File", |_| {});
+        let _edit = menubar.add("Edit", |_| {});
+        let _custom = menubar.insert(2, "Custom", |_| {});
+        assert_eq!(menubar.title(1), "File");
+        assert_eq!(menubar.title(2), "Custom");
+        assert_eq!(menubar.title(3), "Edit");
+    }
+
+    #[test]
+    fn test_standard_app_menu() {
+        let mut menubar = MenuBar::new(|_| {});
+        let app = menubar.add_standard_app_menu("Example");
+        assert_eq!(app.len(), 11);
+        autoreleasepool(|pool| {
+            let quit = app.item_with_title(pool, "Quit Example").unwrap();
+            assert_eq!(quit.key_equivalent(pool), "q");
+            let action: Sel = unsafe { msg_send![quit, action] };
+            assert_eq!(action, sel!(terminate:));
+
+            let hide_others = app.item_with_title(pool, "Hide Others").unwrap();
+            assert_eq!(
+                hide_others.key_equivalent_modifier_mask(),
+                KeyModifiers::COMMAND | KeyModifiers::OPTION
+            );
+
+            let services = app.item_with_title(pool, "Services").unwrap();
+            assert!(services.submenu(pool).is_some());
+        });
+    }
+
+    #[test]
+    fn test_standard_edit_menu() {
+        let mut menubar = MenuBar::new(|_| {});
+        let edit = menubar.add_standard_edit_menu();
+        assert_eq!(edit.title(), "Edit");
+        assert_eq!(edit.len(), 7);
+        autoreleasepool(|pool| {
+            let redo = edit.item_at(pool, 1).unwrap();
+            assert_eq!(redo.title(pool), "Redo");
+            assert_eq!(redo.key_equivalent(pool), "z");
+            assert_eq!(
+                redo.key_equivalent_modifier_mask(),
+                KeyModifiers::COMMAND | KeyModifiers::SHIFT
+            );
+            let copy = edit.item_at(pool, 4).unwrap();
+            let action: Sel = unsafe { msg_send![copy, action] };
+            assert_eq!(action, sel!(copy:));
+            let target: *mut Object = unsafe { msg_send![copy, target] };
+            assert!(target.is_null());
+        });
+    }
+
+    #[test]
+    fn test_key_equivalent_conflicts() {
+        let mut menubar = MenuBar::new(|_| {});
+        let _file = menubar.add("File", |menu| {
+            let _ = menu.add_item_with_key("New", "n", KeyModifiers::COMMAND);
+            let _ = menu.add_item_with_key("New Window", "N", KeyModifiers::COMMAND);
+            let _ = menu.add_item_with_key("Open", "o", KeyModifiers::COMMAND);
+            let _ = menu.add_item_with_key("Close", "w", KeyModifiers::COMMAND);
+        });
+        assert!(menubar.find_key_equivalent_conflicts().is_empty());
+
+        let _edit = menubar.add("Edit", |menu| {
+            // Conflicts with "Open" from inside a submenu
+            let mut item = NSMenuItem::new("Recent", "", None);
+            let mut submenu = NSMenu::new();
+            let _ = submenu.add_item_with_key("Other", "o", KeyModifiers::COMMAND);
+            let _ = item.set_submenu(Some(submenu));
+            let _ = menu.add(item);
+            // Conflicts with "New Window", since "N" implies Shift
+            let _ = menu.add_item_with_key(
+                "Duplicate",
+                "n",
+                KeyModifiers::COMMAND | KeyModifiers::SHIFT,
+            );
+            let _ =
+                menu.add_item_with_key("Again", "n", KeyModifiers::COMMAND | KeyModifiers::SHIFT);
+            // Same key, different modifiers
+            let _ = menu.add_item_with_key(
+                "Close All",
+                "w",
+                KeyModifiers::COMMAND | KeyModifiers::OPTION,
+            );
+            // Hidden items don't conflict
+            let mut hidden = NSMenuItem::new("Hidden", "", None);
+            hidden.set_key_equivalent("w", KeyModifiers::COMMAND);
+            hidden.set_hidden(true);
+            let _ = menu.add(hidden);
+        });
+        assert_eq!(
+            menubar.find_key_equivalent_conflicts(),
+            [
+                ("o".to_owned(), KeyModifiers::COMMAND),
+                ("n".to_owned(), KeyModifiers::COMMAND | KeyModifiers::SHIFT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut menubar = MenuBar::new(|_| {});
+        let _file = menubar.add("File", |menu| {
+            let _ = menu.add_item("Open");
+        });
+        let debug = format!("{:?}", menubar);
+        assert!(debug.starts_with("MenuBar {"));
+        assert!(debug.contains("File"));
+        assert!(debug.contains("Open"));
+    }
+
+    #[test]
+    fn test_titles() {
+        let mut menubar = MenuBar::new(|_| {});
+        let _file = menubar.add("File", |_| {});
+        let _edit = menubar.add_standard_edit_menu();
+        let _window = menubar.add("Window", |_| {});
+        assert_eq!(menubar.titles(), ["", "File", "Edit", "Window"]);
+        menubar.set_title(1, "Document");
+        assert_eq!(menubar.titles(), ["", "Document", "Edit", "Window"]);
+    }
+
+    #[test]
+    fn test_root() {
+        let mut menubar = MenuBar::new(|_| {});
+        let file = menubar.add("File", |_| {});
+        let edit = menubar.add("Edit", |_| {});
+        assert_eq!(menubar.root().len(), 3);
+        autoreleasepool(|pool| {
+            let item = menubar.root().item_at(pool, 1).unwrap();
+            assert_eq!(item.submenu(pool), Some(&*file));
+        });
+
+        let item = menubar.root_mut().remove_at(1);
+        assert_eq!(menubar.title(1), "Edit");
+        autoreleasepool(|pool| {
+            assert_eq!(item.submenu(pool), Some(&*file));
+            let item = menubar.root().item_at(pool, 1).unwrap();
+            assert_eq!(item.submenu(pool), Some(&*edit));
+        });
+    }
+
+    #[test]
+    #[should_panic = "Failed adding standard items: The first item has no menu"]
+    fn test_standard_app_menu_without_app_menu() {
+        let mut menubar = MenuBar::new(|_| {});
+        let _ = menubar.root_mut().remove_at(0);
+        let _ = menubar.root_mut().add(NSMenuItem::new_empty());
+        menubar.add_standard_app_menu("Example");
+    }
+
+    #[test]
+    fn test_title() {
+        let mut menubar = MenuBar::new(|_| {});
+        let _file = menubar.add("File", |_| {});
+        assert_eq!(menubar.title(1), "File");
+        menubar.set_title(1, "Document");
+        assert_eq!(menubar.title(1), "Document");
+    }
+
+    #[test]
+    #[should_panic = "Index 2 out of bounds for number of menus 2"]
+    fn test_title_out_of_bounds() {
+        let mut menubar = MenuBar::new(|_| {});
+        let _file = menubar.add("File", |_| {});
+        menubar.title(2);
+    }
+
+    #[test]
+    #[should_panic = "Index 1 out of bounds for number of items 1"]
+    fn test_remove_out_of_bounds() {
+        let mut menubar = MenuBar::new(|_| {});
+        menubar.remove(1);
+    }
 }
@@ -1,31 +1,47 @@
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
 use core::mem;
 use core::{ffi, fmt, ptr};
-use objc2::ffi::NSInteger;
+use objc2::ffi::{NSInteger, NSUInteger};
 use objc2::rc::{autoreleasepool, AutoreleasePool, Id, Owned, Shared};
-use objc2::runtime::{Bool, Object};
+use objc2::runtime::{Bool, Class, Object, Sel};
 use objc2::{class, msg_send, sel};
 use objc2::{Encoding, Message, RefEncode};
+use objc2_foundation::{NSAttributedString, NSString};
 use std::ptr::NonNull;
-use objc2_foundation::NSString;
-
+use std::rc::Rc;
+
+use super::associated;
+use super::availability;
+use super::image::NSImage;
+use super::keepopen;
+use super::keyequivalent::{self, KeyModifiers};
+use super::leaks;
 use super::menu::NSMenu;
+use super::target;
+use super::view::NSView;
 
-struct Target; // Normal NSObject. Should return YES in worksWhenModal.
-struct ActionSelector; // objc::Sel - a method selector
-struct Image;
-
-#[derive(Debug, PartialEq)]
+/// The state of a menu item, shown in the state column of the menu.
+#[doc(alias = "NSControlStateValue")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MenuItemState {
     /// Checked
+    #[doc(alias = "NSControlStateValueOn")]
     On,
+    /// Shown as a dash by default
+    #[doc(alias = "NSControlStateValueMixed")]
     Mixed,
     /// Unchecked
+    #[doc(alias = "NSControlStateValueOff")]
     Off,
 }
 
 #[repr(C)]
 pub struct NSMenuItem {
     _priv: [u8; 0],
+    /// Makes the item `!Send` and `!Sync`, since its actions and validator
+    /// can be closures that are neither
+    _thread: PhantomData<*mut ()>,
 }
 
 unsafe impl RefEncode for NSMenuItem {
@@ -34,9 +50,6 @@ unsafe impl RefEncode for NSMenuItem {
 
 unsafe impl Message for NSMenuItem {}
 
-unsafe impl Send for NSMenuItem {}
-unsafe impl Sync for NSMenuItem {}
-
 impl NSMenuItem {
     // Defaults:
     //     State: NSOffState
@@ -50,7 +63,9 @@ impl NSMenuItem {
     // Public only locally to allow for construction in Menubar
     pub(super) fn new_empty() -> Id<Self, Owned> {
         let ptr = Self::alloc();
-        unsafe { Id::new(msg_send![ptr, init]).unwrap() }
+        let item: Id<Self, Owned> = unsafe { Id::new(msg_send![ptr, init]).unwrap() };
+        leaks::track(&*item, "NSMenuItem");
+        item
     }
 
     #[doc(alias = "initWithTitle")]
@@ -68,7 +83,7 @@ impl NSMenuItem {
             ptr::null_mut()
         };
         let ptr = Self::alloc();
-        unsafe {
+        let item: Id<Self, Owned> = unsafe {
             Id::new(msg_send![
                 ptr,
                 initWithTitle: &*title,
@@ -76,16 +91,50 @@ impl NSMenuItem {
                 keyEquivalent: &*key_equivalent,
             ])
             .unwrap()
+        };
+        leaks::track(&*item, "NSMenuItem");
+        item
+    }
+
+    /// Create a non-interactive item labelling the section of items below
+    /// it.
+    ///
+    /// Returns `None` on macOS versions older than 14, where section headers
+    /// are not available.
+    #[doc(alias = "sectionHeaderWithTitle")]
+    #[doc(alias = "sectionHeaderWithTitle:")]
+    pub fn section_header(title: &str) -> Option<Id<Self, Owned>> {
+        if !availability::class_responds_to(class!(NSMenuItem), sel!(sectionHeaderWithTitle:)) {
+            return None;
+        }
+        let title = NSString::from_str(title);
+        let item: Option<Id<Self, Owned>> = unsafe {
+            let ptr: *mut Self = msg_send![class!(NSMenuItem), sectionHeaderWithTitle: &*title];
+            // The autoreleased reference is reclaimed by
+            // `retain_autoreleased` (or balanced by the pool otherwise), and
+            // a newly created item isn't referenced by anything else, so
+            // this handle is its only owner
+            Id::retain_autoreleased(ptr)
+        };
+        if let Some(item) = &item {
+            leaks::track(&**item, "NSMenuItem");
         }
+        item
     }
 
+    /// Create a separator item, used to split a menu into logical groups.
+    ///
+    /// Each call creates a new item, so any number of separators can be
+    /// added to the same menu.
     #[doc(alias = "separatorItem")]
     pub fn new_separator() -> Id<Self, Owned> {
         let ptr: *mut Self = unsafe { msg_send![class!(NSMenuItem), separatorItem] };
         // TODO: Find an ergonomic API where we don't need to retain. Also,
         // this has a memory leak if there's no `autoreleasepool` to release
         // the returned pointer.
-        unsafe { Id::retain(ptr).unwrap_unchecked() }
+        let item: Id<Self, Owned> = unsafe { Id::retain(ptr).unwrap_unchecked() };
+        leaks::track(&*item, "NSMenuItem");
+        item
     }
 
     // fn new_separator<'p>(pool: &'p AutoreleasePool) -> &'p mut Self {
@@ -94,14 +143,26 @@ impl NSMenuItem {
 
     // Enabling
 
-    fn enabled(&self) -> bool {
-        unimplemented!()
+    /// Whether the menu item is enabled or greyed out.
+    #[doc(alias = "isEnabled")]
+    pub fn is_enabled(&self) -> bool {
+        let enabled: Bool = unsafe { msg_send![self, isEnabled] };
+        enabled.is_true()
     }
 
+    /// Enable or disable the menu item.
+    ///
+    /// This only has an effect if the owning menu has automatic enabling
+    /// turned off with [`NSMenu::set_autoenables_items`], otherwise the value
+    /// is overwritten every time the menu is validated.
+    ///
+    /// A disabled item that has a submenu can still be opened to show the
+    /// submenu; only the items within it are affected by their own state.
     #[doc(alias = "setEnabled")]
     #[doc(alias = "setEnabled:")]
-    fn set_enabled(&mut self, state: bool) {
-        unimplemented!()
+    pub fn set_enabled(&mut self, enabled: bool) {
+        let enabled = Bool::new(enabled);
+        unsafe { msg_send![self, setEnabled: enabled] }
     }
 
     // Managing Hidden Status
@@ -109,7 +170,8 @@ impl NSMenuItem {
     /// Whether the menu item is hidden or not.
     ///
     /// If hidden, it does not appear in a menu and does not participate in command key matching.
-    pub fn hidden(&self) -> bool {
+    #[doc(alias = "isHidden")]
+    pub fn is_hidden(&self) -> bool {
         let hidden: Bool = unsafe { msg_send![self, isHidden] };
         hidden.is_true()
     }
@@ -121,31 +183,192 @@ impl NSMenuItem {
         unsafe { msg_send![self, setHidden: hidden] }
     }
 
-    // #[doc(alias = "hiddenOrHasHiddenAncestor")]
-    // fn hidden_or_has_hidden_ancestor(&self) -> bool {
-    //     unimplemented!()
-    // }
+    /// Whether the menu item is hidden, or is inside a submenu whose
+    /// parent item is hidden.
+    ///
+    /// Useful to know whether an item is effectively invisible.
+    #[doc(alias = "isHiddenOrHasHiddenAncestor")]
+    pub fn is_hidden_or_has_hidden_ancestor(&self) -> bool {
+        let hidden: Bool = unsafe { msg_send![self, isHiddenOrHasHiddenAncestor] };
+        hidden.is_true()
+    }
 
-    // Target and action
+    /// Whether the key equivalent still works while the item is hidden.
+    ///
+    /// Always `false` on macOS versions older than 10.13, where this is not
+    /// supported.
+    #[doc(alias = "allowsKeyEquivalentWhenHidden")]
+    pub fn allows_key_equivalent_when_hidden(&self) -> bool {
+        let this = unsafe { &*(self as *const Self as *const Object) };
+        if !availability::responds_to(this, sel!(allowsKeyEquivalentWhenHidden)) {
+            return false;
+        }
+        let allows: Bool = unsafe { msg_send![self, allowsKeyEquivalentWhenHidden] };
+        allows.is_true()
+    }
 
-    fn target(&self) -> Target {
-        unimplemented!()
+    /// Keep the key equivalent working while the item is hidden, e.g. for
+    /// shortcuts that shouldn't clutter the menu.
+    ///
+    /// Does nothing on macOS versions older than 10.13, where this is not
+    /// supported.
+    #[doc(alias = "setAllowsKeyEquivalentWhenHidden")]
+    #[doc(alias = "setAllowsKeyEquivalentWhenHidden:")]
+    pub fn set_allows_key_equivalent_when_hidden(&mut self, allows: bool) {
+        let this = unsafe { &*(self as *const Self as *const Object) };
+        if !availability::responds_to(this, sel!(setAllowsKeyEquivalentWhenHidden:)) {
+            return;
+        }
+        let allows = Bool::new(allows);
+        unsafe { msg_send![self, setAllowsKeyEquivalentWhenHidden: allows] }
     }
 
+    // Target and action
+
+    /// Run `handler` whenever the menu item is clicked (or its key
+    /// equivalent is pressed).
+    ///
+    /// The closure is kept alive for as long as the menu item is, and
+    /// replaces any previously set action.
+    #[doc(alias = "setAction")]
+    #[doc(alias = "setAction:")]
     #[doc(alias = "setTarget")]
     #[doc(alias = "setTarget:")]
-    fn set_target(&mut self, target: Target) {
-        unimplemented!()
+    pub fn set_action(&mut self, handler: impl Fn() + 'static) {
+        self.set_action_with_item(move |_| handler());
     }
 
-    fn action(&self) -> ActionSelector {
-        unimplemented!()
+    /// Like [`set_action`], but `handler` is given the item that was clicked.
+    ///
+    /// This allows one handler to be shared between several items, and
+    /// branch on e.g. their [`tag`] or [`state`]. The item is only borrowed
+    /// for the duration of the call.
+    ///
+    /// [`set_action`]: Self::set_action
+    /// [`tag`]: Self::tag
+    /// [`state`]: Self::state
+    pub fn set_action_with_item(&mut self, handler: impl Fn(&NSMenuItem) + 'static) {
+        self.update_target(|callbacks| callbacks.handler = Some(Rc::new(handler)));
     }
 
+    /// Decide whether the item is enabled by running `validator` just before
+    /// the item is shown, instead of enabling and disabling it manually.
+    ///
+    /// This only has an effect if the menu [automatically enables its
+    /// items](super::NSMenu::set_autoenables_items), which is the default.
+    /// The closure is kept alive for as long as the menu item is, and is
+    /// kept when the action is changed with [`set_action`].
+    ///
+    /// [`set_action`]: Self::set_action
+    #[doc(alias = "validateMenuItem")]
+    #[doc(alias = "validateMenuItem:")]
+    pub fn set_validator(&mut self, validator: impl Fn() -> bool + 'static) {
+        self.update_target(|callbacks| callbacks.validator = Some(Rc::new(validator)));
+    }
+
+    /// Send `action` to an existing Objective-C object when the item is
+    /// clicked, instead of to a Rust closure, e.g. to integrate with an
+    /// application delegate written in Objective-C.
+    ///
+    /// With a `None` target, the action is sent through the responder chain
+    /// instead, starting at the first responder of the key window, and the
+    /// item is only enabled if something in the chain implements `action`.
+    /// This is how standard items like Copy and Paste are set up.
+    ///
+    /// The target is kept alive for as long as the menu item is (AppKit does
+    /// not retain targets). This replaces any closures set with
+    /// [`set_action`] or [`set_validator`].
+    ///
+    /// [`set_action`]: Self::set_action
+    /// [`set_validator`]: Self::set_validator
     #[doc(alias = "setAction")]
     #[doc(alias = "setAction:")]
-    fn set_action(&mut self, action: ActionSelector) {
-        unimplemented!()
+    #[doc(alias = "setTarget")]
+    #[doc(alias = "setTarget:")]
+    pub fn set_target_action(&mut self, target: Option<&Object>, action: Sel) {
+        let this = self as *const Self as *const Object;
+        // SAFETY: The item is a valid object, and will retain the target
+        unsafe { associated::set(this, &target::ASSOCIATION_KEY, target) };
+        let _: () = unsafe { msg_send![&mut *self, setTarget: target] };
+        unsafe { msg_send![self, setAction: action] }
+    }
+
+    /// Install a new target with the callbacks of the current one, modified
+    /// by `f`.
+    fn update_target(&mut self, f: impl FnOnce(&mut target::Callbacks)) {
+        let this = self as *const Self as *const Object;
+        let existing = unsafe { associated::get(this, &target::ASSOCIATION_KEY) };
+        let mut callbacks = unsafe { existing.as_ref() }
+            .and_then(target::callbacks)
+            .unwrap_or_default();
+        f(&mut callbacks);
+        let target = target::new(callbacks);
+        // SAFETY: The item is a valid object, and will retain the target
+        unsafe { associated::set(this, &target::ASSOCIATION_KEY, Some(&target)) };
+        let action = target::action();
+        let _: () = unsafe { msg_send![&mut *self, setTarget: &*target] };
+        unsafe { msg_send![self, setAction: action] }
+    }
+
+    /// Move the properties of `template` onto `item`, for updating an item
+    /// that AppKit owns (like those of a lazily populated menu) without
+    /// creating a `&mut` to it.
+    pub(super) fn move_properties(template: Id<Self, Owned>, item: &Self) {
+        macro_rules! copy {
+            ($ty:ty, $get:ident, $set:ident) => {{
+                let value: $ty = unsafe { msg_send![&*template, $get] };
+                let _: () = unsafe { msg_send![item, $set: value] };
+            }};
+        }
+        copy!(*mut Object, title, setTitle);
+        copy!(*mut Object, attributedTitle, setAttributedTitle);
+        copy!(*mut Object, keyEquivalent, setKeyEquivalent);
+        copy!(
+            NSUInteger,
+            keyEquivalentModifierMask,
+            setKeyEquivalentModifierMask
+        );
+        copy!(Bool, isAlternate, setAlternate);
+        let item_obj = unsafe { &*(item as *const Self as *const Object) };
+        if availability::responds_to(item_obj, sel!(setAllowsKeyEquivalentWhenHidden:)) {
+            copy!(
+                Bool,
+                allowsKeyEquivalentWhenHidden,
+                setAllowsKeyEquivalentWhenHidden
+            );
+        }
+        copy!(NSInteger, state, setState);
+        copy!(Bool, isEnabled, setEnabled);
+        copy!(Bool, isHidden, setHidden);
+        copy!(NSInteger, tag, setTag);
+        copy!(*mut Object, image, setImage);
+        copy!(*mut Object, onStateImage, setOnStateImage);
+        copy!(*mut Object, offStateImage, setOffStateImage);
+        copy!(*mut Object, mixedStateImage, setMixedStateImage);
+        copy!(NSInteger, indentationLevel, setIndentationLevel);
+        copy!(*mut Object, toolTip, setToolTip);
+        copy!(*mut Object, representedObject, setRepresentedObject);
+        copy!(*mut Object, target, setTarget);
+        copy!(Sel, action, setAction);
+
+        // Keep closure targets alive
+        let template_ptr = &*template as *const Self as *const Object;
+        let item_ptr = item as *const Self as *const Object;
+        unsafe {
+            let target = associated::get(template_ptr, &target::ASSOCIATION_KEY);
+            associated::set(item_ptr, &target::ASSOCIATION_KEY, target.as_ref());
+        }
+
+        // Submenus and views can only belong to one item, so they are
+        // detached from the template first
+        let submenu: Option<Id<NSMenu, Shared>> =
+            unsafe { Id::retain(msg_send![&*template, submenu]) };
+        let view: Option<Id<NSView, Shared>> = unsafe { Id::retain(msg_send![&*template, view]) };
+        let none: *const Object = ptr::null();
+        let _: () = unsafe { msg_send![&*template, setSubmenu: none] };
+        let _: () = unsafe { msg_send![&*template, setView: none] };
+        let _: () = unsafe { msg_send![item, setSubmenu: submenu.as_deref()] };
+        let _: () = unsafe { msg_send![item, setView: view.as_deref()] };
     }
 
     // Title
@@ -155,6 +378,12 @@ impl NSMenuItem {
         title.as_str(pool)
     }
 
+    /// Like [`title`](Self::title), but returns an owned copy so that no
+    /// autorelease pool is needed.
+    pub fn title_owned(&self) -> String {
+        autoreleasepool(|pool| self.title(pool).to_owned())
+    }
+
     #[doc(alias = "setTitle")]
     #[doc(alias = "setTitle:")]
     pub fn set_title(&mut self, title: &str) {
@@ -162,32 +391,55 @@ impl NSMenuItem {
         unsafe { msg_send![self, setTitle: &*title] }
     }
 
-    // #[doc(alias = "attributedTitle")]
-    // pub fn attributed_title(&self) -> ??? { unimplemented!() }
-    // #[doc(alias = "setAttributedTitle")]
-    // #[doc(alias = "setAttributedTitle:")]
-    // pub fn set_attributed_title(&mut self, title: ???) { unimplemented!() }
+    /// The styled title, if one has been set with
+    /// [`set_attributed_title`](Self::set_attributed_title).
+    #[doc(alias = "attributedTitle")]
+    pub fn attributed_title<'p>(
+        &self,
+        pool: &'p AutoreleasePool,
+    ) -> Option<&'p NSAttributedString> {
+        unsafe { msg_send![self, attributedTitle] }
+    }
+
+    /// Set a styled title, e.g. to show a dimmed secondary string after the
+    /// name. Build one with [`AttributedStringBuilder`].
+    ///
+    /// While set, the attributed title is shown instead of the plain
+    /// [`title`](Self::title). `None` goes back to showing the plain title.
+    ///
+    /// [`AttributedStringBuilder`]: super::AttributedStringBuilder
+    #[doc(alias = "setAttributedTitle")]
+    #[doc(alias = "setAttributedTitle:")]
+    pub fn set_attributed_title(&mut self, title: Option<&NSAttributedString>) {
+        unsafe { msg_send![self, setAttributedTitle: title] }
+    }
 
     // Tag
 
-    fn tag(&self) -> isize {
-        unimplemented!()
+    /// An integer identifying the menu item, `0` by default.
+    pub fn tag(&self) -> isize {
+        let tag: NSInteger = unsafe { msg_send![self, tag] };
+        // NSInteger has the same width as isize on all supported targets
+        tag as isize
     }
 
     #[doc(alias = "setTag")]
     #[doc(alias = "setTag:")]
-    fn set_tag(&mut self, tag: isize) {
-        unimplemented!()
+    pub fn set_tag(&mut self, tag: isize) {
+        let tag = tag as NSInteger;
+        unsafe { msg_send![self, setTag: tag] }
     }
 
     /// Get the menu item's state
+    ///
+    /// Unknown state values (which can only be set outside of this crate)
+    /// are reported as [`MenuItemState::Off`].
     pub fn state(&self) -> MenuItemState {
         let state: NSInteger = unsafe { msg_send![self, state] };
         match state {
             1 => MenuItemState::On,
             -1 => MenuItemState::Mixed,
-            0 => MenuItemState::Off,
-            _ => unreachable!(),
+            _ => MenuItemState::Off,
         }
     }
 
@@ -210,43 +462,91 @@ impl NSMenuItem {
 
     // Images
 
-    fn image(&self) -> Option<&Image> {
-        unimplemented!()
+    /// The icon shown next to the title, if any.
+    pub fn image<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSImage> {
+        unsafe { msg_send![self, image] }
     }
 
+    /// Set the icon shown next to the title. `None` removes it.
+    ///
+    /// Menu items don't scale their image; it's drawn at its own
+    /// [size](NSImage::size), which makes the item taller if needed. Use
+    /// [`NSImage::set_size`] to make large images fit, e.g. 16x16 points to
+    /// match the height of the title.
     #[doc(alias = "setImage")]
     #[doc(alias = "setImage:")]
-    fn set_image(&mut self, image: Option<&Image>) {
-        unimplemented!()
+    pub fn set_image(&mut self, image: Option<&NSImage>) {
+        unsafe { msg_send![self, setImage: image] }
     }
 
+    /// The image shown in the state column when the item is in the given
+    /// state.
+    ///
+    /// By default, this is a checkmark for [`MenuItemState::On`], a dash for
+    /// [`MenuItemState::Mixed`] and nothing for [`MenuItemState::Off`].
     #[doc(alias = "onStateImage")]
     #[doc(alias = "offStateImage")]
     #[doc(alias = "mixedStateImage")]
-    fn image_for_state<'p>(
+    pub fn image_for_state<'p>(
         &self,
         pool: &'p AutoreleasePool,
         state: MenuItemState,
-    ) -> Option<&'p Image> {
-        unimplemented!()
+    ) -> Option<&'p NSImage> {
+        unsafe {
+            match state {
+                MenuItemState::On => msg_send![self, onStateImage],
+                MenuItemState::Mixed => msg_send![self, mixedStateImage],
+                MenuItemState::Off => msg_send![self, offStateImage],
+            }
+        }
     }
 
+    /// Set a custom image to show in the state column when the item is in
+    /// the given state, e.g. a dot instead of a checkmark.
+    ///
+    /// `None` restores the default image for that state.
     #[doc(alias = "setOnStateImage")]
     #[doc(alias = "setOnStateImage:")]
     #[doc(alias = "setOffStateImage")]
     #[doc(alias = "setOffStateImage:")]
     #[doc(alias = "setMixedStateImage")]
     #[doc(alias = "setMixedStateImage:")]
-    fn set_image_for_state(&mut self, state: MenuItemState, image: Option<&Image>) {
-        unimplemented!()
+    pub fn set_image_for_state(&mut self, state: MenuItemState, image: Option<&NSImage>) {
+        autoreleasepool(|pool| {
+            // AppKit shows no image at all when set to `nil`, so copy the
+            // default from a fresh item instead
+            let default;
+            let image = match image {
+                Some(image) => Some(image),
+                None => {
+                    default = Self::new_empty();
+                    default.image_for_state(pool, state)
+                }
+            };
+            unsafe {
+                match state {
+                    MenuItemState::On => msg_send![&mut *self, setOnStateImage: image],
+                    MenuItemState::Mixed => msg_send![&mut *self, setMixedStateImage: image],
+                    MenuItemState::Off => msg_send![&mut *self, setOffStateImage: image],
+                }
+            }
+        })
     }
 
     // Submenus
 
+    /// The submenu opened by this item, if any.
+    ///
+    /// Also works for menus the crate didn't build, like the system Window
+    /// menu.
     pub fn submenu<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenu> {
         unsafe { msg_send![self, submenu] }
     }
 
+    /// Set the submenu opened by this item, returning a shared handle to it.
+    ///
+    /// `None` detaches the current submenu, if any. The menu is taken by
+    /// value, since a menu can only be the submenu of one item.
     #[doc(alias = "setSubmenu")]
     #[doc(alias = "setSubmenu:")]
     pub fn set_submenu(
@@ -262,29 +562,31 @@ impl NSMenuItem {
         menu.map(|obj| obj.into())
     }
 
+    /// Whether the item has a submenu.
     #[doc(alias = "hasSubmenu")]
-    fn has_submenu(&self) -> bool {
-        unimplemented!()
+    pub fn has_submenu(&self) -> bool {
+        let has_submenu: Bool = unsafe { msg_send![self, hasSubmenu] };
+        has_submenu.is_true()
     }
 
-    /// The parent submenu's menuitem
+    /// The item whose submenu contains this item, or `None` if this item
+    /// is not inside a submenu.
     #[doc(alias = "parentItem")]
-    fn parent_item<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenuItem> {
-        unimplemented!()
+    pub fn parent_item<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenuItem> {
+        unsafe { msg_send![self, parentItem] }
     }
 
     #[doc(alias = "isSeparatorItem")]
-    pub fn separator(&self) -> bool {
-        // TODO: Maybe call this is_separator?
+    pub fn is_separator(&self) -> bool {
         let is_separator: Bool = unsafe { msg_send![self, isSeparatorItem] };
         is_separator.is_true()
     }
 
     // Owning menu
 
-    #[doc(alias = "menu")]
-    fn parent_menu<'p>(&self, pool: &'p AutoreleasePool) -> &'p NSMenu {
-        unimplemented!()
+    /// The menu this item is in, or `None` if it hasn't been added to one.
+    pub fn menu<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenu> {
+        unsafe { msg_send![self, menu] }
     }
 
     #[doc(alias = "setMenu")]
@@ -295,80 +597,313 @@ impl NSMenuItem {
 
     // Handling keyboard events
 
-    // fn key_equvalent()
-    // fn key_equvalent_something_modifiers()
+    /// The key that, together with [`key_equivalent_modifier_mask`], triggers
+    /// this item. Empty if the item has no key equivalent.
+    ///
+    /// [`key_equivalent_modifier_mask`]: Self::key_equivalent_modifier_mask
+    #[doc(alias = "keyEquivalent")]
+    pub fn key_equivalent<'p>(&self, pool: &'p AutoreleasePool) -> &'p str {
+        let key: &NSString = unsafe { msg_send![self, keyEquivalent] };
+        key.as_str(pool)
+    }
+
+    #[doc(alias = "keyEquivalentModifierMask")]
+    pub fn key_equivalent_modifier_mask(&self) -> KeyModifiers {
+        let mask: NSUInteger = unsafe { msg_send![self, keyEquivalentModifierMask] };
+        KeyModifiers::from_bits_truncate(mask)
+    }
+
+    /// Set the keyboard shortcut of the menu item.
+    ///
+    /// A single uppercase letter implies [`KeyModifiers::SHIFT`], so `"N"`
+    /// with [`KeyModifiers::COMMAND`] is stored as `"n"` with
+    /// `COMMAND | SHIFT`. Passing an empty string clears the shortcut.
+    ///
+    /// Use [`KeyEquivalent`] for function keys, arrows and other keys that
+    /// don't have a printable character.
+    ///
+    /// [`KeyEquivalent`]: super::KeyEquivalent
+    ///
+    /// ```no_run
+    /// use menubar::appkit::{KeyModifiers, NSMenuItem};
+    ///
+    /// let mut item = NSMenuItem::new("Quit", "", None);
+    /// item.set_key_equivalent("q", KeyModifiers::COMMAND);
+    /// ```
+    #[doc(alias = "setKeyEquivalent")]
+    #[doc(alias = "setKeyEquivalent:")]
+    #[doc(alias = "setKeyEquivalentModifierMask")]
+    #[doc(alias = "setKeyEquivalentModifierMask:")]
+    pub fn set_key_equivalent(&mut self, key: &str, modifiers: KeyModifiers) {
+        let (key, modifiers) = keyequivalent::normalize_key_equivalent(key, modifiers);
+        let key = NSString::from_str(&key);
+        let mask = modifiers.bits();
+        let _: () = unsafe { msg_send![&mut *self, setKeyEquivalent: &*key] };
+        unsafe { msg_send![self, setKeyEquivalentModifierMask: mask] }
+    }
+
     // fn something_user_key_equvalents
-    // fn user_key_equvalent() (readonly)
+
+    /// The key equivalent the user has assigned to this item in System
+    /// Preferences, or an empty string if there is none.
+    ///
+    /// This can differ from [`key_equivalent`](Self::key_equivalent), and is
+    /// the one that is actually used. It's read-only, since it's controlled
+    /// by the user.
+    #[doc(alias = "userKeyEquivalent")]
+    pub fn user_key_equivalent(&self) -> String {
+        autoreleasepool(|pool| {
+            let key: Option<&NSString> = unsafe { msg_send![self, userKeyEquivalent] };
+            key.map(|key| key.as_str(pool).to_owned())
+                .unwrap_or_default()
+        })
+    }
 
     // Marks the menu item as an alternate to the previous menu item
 
-    fn alternate(&self) -> bool {
-        unimplemented!()
+    #[doc(alias = "isAlternate")]
+    pub fn is_alternate(&self) -> bool {
+        let alternate: Bool = unsafe { msg_send![self, isAlternate] };
+        alternate.is_true()
     }
 
+    /// Mark the menu item as an alternate to the item before it.
+    ///
+    /// The alternate is only shown, in place of the primary item, while the
+    /// user holds down the extra modifier keys. For this to work, the
+    /// alternate must immediately follow the primary item in the menu, and
+    /// both must have the same key equivalent but differing modifier masks.
+    ///
+    /// ```no_run
+    /// use menubar::appkit::{KeyModifiers, NSMenu, NSMenuItem};
+    ///
+    /// let mut menu = NSMenu::new();
+    ///
+    /// let mut close = NSMenuItem::new("Close", "", None);
+    /// close.set_key_equivalent("w", KeyModifiers::COMMAND);
+    /// menu.add(close);
+    ///
+    /// // Shown instead of "Close" while Option is held
+    /// let mut close_all = NSMenuItem::new("Close All", "", None);
+    /// close_all.set_key_equivalent("w", KeyModifiers::COMMAND | KeyModifiers::OPTION);
+    /// close_all.set_alternate(true);
+    /// menu.add(close_all);
+    /// ```
     #[doc(alias = "setAlternate")]
     #[doc(alias = "setAlternate:")]
-    fn set_alternate(&mut self, alternate: bool) {
-        unimplemented!()
+    pub fn set_alternate(&mut self, alternate: bool) {
+        let alternate = Bool::new(alternate);
+        unsafe { msg_send![self, setAlternate: alternate] }
     }
 
     // Indentation level (0-15)
 
     #[doc(alias = "indentationLevel")]
-    fn indentation_level(&self) -> isize {
-        unimplemented!()
+    pub fn indentation_level(&self) -> u8 {
+        let level: NSInteger = unsafe { msg_send![self, indentationLevel] };
+        level as u8
     }
 
+    /// Visually indent the item, to create tree-like lists without using
+    /// submenus.
+    ///
+    /// AppKit only supports levels from 0 to 15, so values above 15 are
+    /// clamped to 15.
     #[doc(alias = "setIndentationLevel")]
     #[doc(alias = "setIndentationLevel:")]
-    fn set_indentation_level(&mut self, level: isize) {
-        unimplemented!()
+    pub fn set_indentation_level(&mut self, level: u8) {
+        let level = level.min(15) as NSInteger;
+        unsafe { msg_send![self, setIndentationLevel: level] }
     }
 
     // Tooltop / help tag
 
+    /// The help tag shown when hovering the item, or `None` if unset.
     #[doc(alias = "toolTip")]
-    fn tooltip(&self) -> &str {
-        unimplemented!()
+    pub fn tooltip<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p str> {
+        let tooltip: Option<&NSString> = unsafe { msg_send![self, toolTip] };
+        tooltip.map(|tooltip| tooltip.as_str(pool))
     }
 
+    /// Set the help tag of the item. `None` removes it.
+    ///
+    /// Useful for explaining why an item is disabled, or for showing the
+    /// full text of an abbreviated title.
     #[doc(alias = "setToolTip")]
     #[doc(alias = "setToolTip:")]
-    fn set_tooltip(&mut self, tooltip: &str) {
-        unimplemented!()
+    pub fn set_tooltip(&mut self, tooltip: Option<&str>) {
+        let tooltip = tooltip.map(NSString::from_str);
+        unsafe { msg_send![self, setToolTip: tooltip.as_deref()] }
+    }
+
+    // Badges
+
+    /// Show a count badge next to the item, e.g. the number of unread
+    /// messages. `None` removes the badge.
+    ///
+    /// Badges require macOS 14 or newer; on older versions this does
+    /// nothing.
+    #[doc(alias = "setBadge")]
+    #[doc(alias = "setBadge:")]
+    #[doc(alias = "updatesWithCount")]
+    #[doc(alias = "updatesWithCount:")]
+    pub fn set_badge_count(&mut self, count: Option<u64>) {
+        let cls = match Class::get("NSMenuItemBadge") {
+            Some(cls) => cls,
+            None => return,
+        };
+        let badge: *mut Object = match count {
+            Some(count) => unsafe { msg_send![cls, updatesWithCount: count as NSInteger] },
+            None => ptr::null_mut(),
+        };
+        unsafe { msg_send![self, setBadge: badge] }
+    }
+
+    /// Show a text badge next to the item. `None` removes the badge.
+    ///
+    /// Badges require macOS 14 or newer; on older versions this does
+    /// nothing.
+    #[doc(alias = "setBadge")]
+    #[doc(alias = "setBadge:")]
+    #[doc(alias = "initWithString")]
+    #[doc(alias = "initWithString:")]
+    pub fn set_badge_text(&mut self, text: Option<&str>) {
+        let cls = match Class::get("NSMenuItemBadge") {
+            Some(cls) => cls,
+            None => return,
+        };
+        let badge: Option<Id<Object, Owned>> = text.map(|text| {
+            let text = NSString::from_str(text);
+            unsafe {
+                let ptr: *mut Object = msg_send![cls, alloc];
+                Id::new(msg_send![ptr, initWithString: &*text]).unwrap()
+            }
+        });
+        unsafe { msg_send![self, setBadge: badge.as_deref()] }
     }
 
     // Represented object (kinda like tags)
 
+    /// The object associated with the item, if any.
     #[doc(alias = "representedObject")]
-    fn represented_object(&self) -> *const Object {
-        unimplemented!()
+    pub fn represented_object<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p Object> {
+        unsafe { msg_send![self, representedObject] }
     }
 
+    /// Associate an arbitrary Objective-C object with the item. The item
+    /// keeps the object alive.
+    ///
+    /// Most users will want [`set_represented_tag`] instead.
+    ///
+    /// [`set_represented_tag`]: Self::set_represented_tag
     #[doc(alias = "setRepresentedObject")]
     #[doc(alias = "setRepresentedObject:")]
-    fn set_represented_object(&mut self, tooltip: *mut Object) {
-        unimplemented!()
+    pub fn set_represented_object(&mut self, object: Option<&Object>) {
+        unsafe { msg_send![self, setRepresentedObject: object] }
+    }
+
+    /// The integer stored with [`set_represented_tag`], or `None` if the
+    /// represented object is not a number.
+    ///
+    /// [`set_represented_tag`]: Self::set_represented_tag
+    pub fn represented_tag(&self) -> Option<i64> {
+        autoreleasepool(|pool| {
+            let object = self.represented_object(pool)?;
+            let is_number: Bool = unsafe { msg_send![object, isKindOfClass: class!(NSNumber)] };
+            if is_number.is_true() {
+                Some(unsafe { msg_send![object, longLongValue] })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Store an integer with the item, boxed in an `NSNumber`.
+    ///
+    /// Useful for routing clicks back to Rust code.
+    #[doc(alias = "setRepresentedObject")]
+    #[doc(alias = "setRepresentedObject:")]
+    pub fn set_represented_tag(&mut self, value: i64) {
+        let number: Id<Object, Shared> = unsafe {
+            let ptr: *mut Object = msg_send![class!(NSNumber), numberWithLongLong: value];
+            Id::retain_autoreleased(ptr).unwrap()
+        };
+        self.set_represented_object(Some(&number));
     }
 
     // View - most other attributes are ignore if this is set
 
-    fn view(&self) -> *const Object {
-        unimplemented!()
+    /// The custom view shown instead of the item's title and image, if any.
+    pub fn view<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSView> {
+        unsafe { msg_send![self, view] }
     }
 
+    /// Show a custom view in place of the item, e.g. a volume slider.
+    ///
+    /// The view replaces the normal drawing of the title, image and state,
+    /// and is responsible for drawing its own highlight, e.g. by checking
+    /// `isHighlighted` of its enclosing item. `None` goes back to the normal
+    /// drawing.
+    ///
+    /// The item retains the view.
     #[doc(alias = "setView")]
     #[doc(alias = "setView:")]
-    fn set_view(&mut self, tooltip: *mut Object) {
-        unimplemented!()
+    pub fn set_view(&mut self, view: Option<&NSView>) {
+        unsafe { msg_send![self, setView: view] }
+    }
+
+    /// Whether clicking the item keeps the menu open, see
+    /// [`set_keeps_menu_open`](Self::set_keeps_menu_open).
+    pub fn keeps_menu_open(&self) -> bool {
+        autoreleasepool(|pool| match self.view(pool) {
+            Some(view) => keepopen::is_keep_open_view(view),
+            None => false,
+        })
+    }
+
+    /// Keep the menu open when the item is clicked, e.g. for checkbox-like
+    /// items where the user may want to toggle several in a row.
+    ///
+    /// AppKit always closes the menu when a regular item is chosen, but
+    /// clicks on an item with a custom [`view`](Self::view) are handled by
+    /// the view instead. So this gives the item a view that draws its
+    /// title, state checkmark and highlight, and sends the item's action
+    /// itself when clicked.
+    ///
+    /// This has some limitations, since the item is no longer drawn by
+    /// AppKit:
+    /// - The drawing only approximates that of regular items, and the image,
+    ///   key equivalent, attributed title, indentation and submenu arrow are
+    ///   not drawn.
+    /// - Choosing the item with the keyboard (by pressing Return) still
+    ///   closes the menu, and its key equivalent still works as usual.
+    /// - Any view previously set with [`set_view`](Self::set_view) is
+    ///   replaced, and turning this off removes the view again.
+    pub fn set_keeps_menu_open(&mut self, keep: bool) {
+        if keep == self.keeps_menu_open() {
+            return;
+        }
+        if keep {
+            let view = autoreleasepool(|_| {
+                let title: &NSString = unsafe { msg_send![&*self, title] };
+                keepopen::new(title)
+            });
+            self.set_view(Some(&view));
+        } else {
+            self.set_view(None);
+        }
     }
 
-    /// Get whether the menu should be drawn highlighted
+    /// Whether the item is currently highlighted in an open menu.
     ///
-    /// You should probably use the [`NSMenu`] delegate method "willHighlightItem"
+    /// Useful for items with a custom [`view`](Self::view), which have to
+    /// draw the highlight themselves. This is read-only, AppKit decides
+    /// which item is highlighted; see [`NSMenu::highlighted_item`] and
+    /// [`NSMenu::set_highlight_handler`] for observing changes.
     #[doc(alias = "isHighlighted")]
-    fn highlighted(&self) -> bool {
-        unimplemented!()
+    pub fn is_highlighted(&self) -> bool {
+        let highlighted: Bool = unsafe { msg_send![self, isHighlighted] };
+        highlighted.is_true()
     }
 
     // Protocols: Same as NSMenu + "NSValidatedUserInterfaceItem"
@@ -376,6 +911,8 @@ impl NSMenuItem {
     // automatically enable and disable items based on context
 }
 
+/// Equality is reference identity: two values are equal only if they are
+/// the same object, not if e.g. their titles are the same.
 impl PartialEq for NSMenuItem {
     /// Pointer equality
     #[inline]
@@ -384,14 +921,24 @@ impl PartialEq for NSMenuItem {
     }
 }
 
+impl Eq for NSMenuItem {}
+
+impl Hash for NSMenuItem {
+    /// Hashes the pointer, consistent with [`PartialEq`]
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self as *const Self).hash(state)
+    }
+}
+
 impl fmt::Debug for NSMenuItem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         autoreleasepool(|pool| {
             f.debug_struct("NSMenuItem")
                 .field("id", &(self as *const Self))
-                .field("separator", &self.separator())
+                .field("separator", &self.is_separator())
                 .field("title", &self.title(pool))
-                .field("hidden", &self.hidden())
+                .field("hidden", &self.is_hidden())
                 .field("state", &self.state())
                 .field("submenu", &self.submenu(pool))
                 // TODO: parent?
@@ -415,11 +962,58 @@ mod tests {
     fn test_hidden() {
         autoreleasepool(|pool| {
             for_each_item(pool, |item| {
-                assert!(!item.hidden());
+                assert!(!item.is_hidden());
                 item.set_hidden(true);
-                assert!(item.hidden());
+                assert!(item.is_hidden());
                 item.set_hidden(false);
-                assert!(!item.hidden());
+                assert!(!item.is_hidden());
+            })
+        });
+    }
+
+    #[test]
+    fn test_allows_key_equivalent_when_hidden() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                assert!(!item.allows_key_equivalent_when_hidden());
+                item.set_allows_key_equivalent_when_hidden(true);
+                assert!(item.allows_key_equivalent_when_hidden());
+                item.set_allows_key_equivalent_when_hidden(false);
+                assert!(!item.allows_key_equivalent_when_hidden());
+            });
+        });
+    }
+
+    #[test]
+    fn test_hidden_ancestor() {
+        autoreleasepool(|_| {
+            for &hidden in &[false, true] {
+                let mut root = NSMenu::new();
+                let mut parent = NSMenuItem::new("parent", "", None);
+                let mut submenu = NSMenu::new();
+                // Hiding the only other item in a menu is allowed
+                let mut other = NSMenuItem::new("other", "", None);
+                other.set_hidden(true);
+                submenu.add(other);
+                let child = submenu.add(NSMenuItem::new("child", "", None));
+                parent.set_submenu(Some(submenu));
+                parent.set_hidden(hidden);
+                root.add(parent);
+
+                assert!(!child.is_hidden());
+                assert_eq!(child.is_hidden_or_has_hidden_ancestor(), hidden);
+            }
+        });
+    }
+
+    #[test]
+    fn test_enabled() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                item.set_enabled(false);
+                assert!(!item.is_enabled());
+                item.set_enabled(true);
+                assert!(item.is_enabled());
             })
         });
     }
@@ -436,6 +1030,51 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_tooltip() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                assert_eq!(item.tooltip(pool), None);
+                STRINGS
+                    .iter()
+                    .filter(|s| !s.is_empty())
+                    .for_each(|&tooltip| {
+                        item.set_tooltip(Some(tooltip));
+                        assert_eq!(item.tooltip(pool), Some(tooltip));
+                    });
+                item.set_tooltip(None);
+                assert_eq!(item.tooltip(pool), None);
+            });
+        });
+    }
+
+    #[test]
+    fn test_attributed_title() {
+        autoreleasepool(|pool| {
+            let title = crate::appkit::AttributedStringBuilder::new("styled").build();
+            for_each_item(pool, |item| {
+                assert!(item.attributed_title(pool).is_none());
+                item.set_attributed_title(Some(&title));
+                let attributed = item.attributed_title(pool).unwrap();
+                assert_eq!(attributed.string().as_str(pool), "styled");
+                item.set_attributed_title(None);
+                assert!(item.attributed_title(pool).is_none());
+            });
+        });
+    }
+
+    #[test]
+    fn test_title_owned() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                for &title in STRINGS.iter() {
+                    item.set_title(title);
+                    assert_eq!(item.title_owned(), title);
+                }
+            });
+        });
+    }
+
     #[test]
     fn test_title_init() {
         autoreleasepool(|pool| {
@@ -460,11 +1099,61 @@ mod tests {
     fn test_separator() {
         autoreleasepool(|_| {
             let item = NSMenuItem::new_separator();
-            assert!(item.separator());
+            assert!(item.is_separator());
             let item = NSMenuItem::new_empty();
-            assert!(!item.separator());
+            assert!(!item.is_separator());
             let item = NSMenuItem::new("", "", None);
-            assert!(!item.separator());
+            assert!(!item.is_separator());
+        });
+    }
+
+    #[test]
+    fn test_section_header() {
+        autoreleasepool(|pool| {
+            if let Some(item) = NSMenuItem::section_header("Recent") {
+                assert_eq!(item.title(pool), "Recent");
+                let is_header: Bool = unsafe { msg_send![&*item, isSectionHeader] };
+                assert!(is_header.is_true());
+            }
+        });
+    }
+
+    #[test]
+    fn test_separator_distinct() {
+        autoreleasepool(|_| {
+            let a = NSMenuItem::new_separator();
+            let b = NSMenuItem::new_separator();
+            assert_ne!(&*a, &*b);
+        });
+    }
+
+    #[test]
+    fn test_tag() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                assert_eq!(item.tag(), 0);
+                item.set_tag(42);
+                assert_eq!(item.tag(), 42);
+                item.set_tag(isize::MIN);
+                assert_eq!(item.tag(), isize::MIN);
+            });
+        });
+    }
+
+    #[test]
+    fn test_indentation_level() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                assert_eq!(item.indentation_level(), 0);
+                item.set_indentation_level(3);
+                assert_eq!(item.indentation_level(), 3);
+                item.set_indentation_level(15);
+                assert_eq!(item.indentation_level(), 15);
+                item.set_indentation_level(16);
+                assert_eq!(item.indentation_level(), 15);
+                item.set_indentation_level(u8::MAX);
+                assert_eq!(item.indentation_level(), 15);
+            });
         });
     }
 
@@ -483,17 +1172,424 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_state_unknown() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                item.set_state(MenuItemState::On);
+                assert_eq!(item.state(), MenuItemState::On);
+                let _: () = unsafe { msg_send![&mut *item, setState: 2 as NSInteger] };
+                assert_eq!(item.state(), MenuItemState::Off);
+            });
+        });
+    }
+
+    #[test]
+    fn test_key_equivalent() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                item.set_key_equivalent("q", KeyModifiers::COMMAND);
+                assert_eq!(item.key_equivalent(pool), "q");
+                assert_eq!(item.key_equivalent_modifier_mask(), KeyModifiers::COMMAND);
+
+                item.set_key_equivalent("N", KeyModifiers::COMMAND);
+                assert_eq!(item.key_equivalent(pool), "n");
+                assert_eq!(
+                    item.key_equivalent_modifier_mask(),
+                    KeyModifiers::COMMAND | KeyModifiers::SHIFT
+                );
+
+                item.set_key_equivalent("", KeyModifiers::empty());
+                assert_eq!(item.key_equivalent(pool), "");
+            });
+        });
+    }
+
+    #[test]
+    fn test_user_key_equivalent() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                // Not customized by the user
+                item.set_key_equivalent("q", KeyModifiers::COMMAND);
+                assert_eq!(item.user_key_equivalent(), "");
+            });
+        });
+    }
+
+    #[test]
+    fn test_image() {
+        autoreleasepool(|pool| {
+            let image = NSImage::from_system_symbol("folder").unwrap();
+            for_each_item(pool, |item| {
+                assert!(item.image(pool).is_none());
+                item.set_image(Some(&image));
+                assert_eq!(item.image(pool), Some(&*image));
+                item.set_image(None);
+                assert!(item.image(pool).is_none());
+            });
+        });
+    }
+
+    #[test]
+    fn test_image_for_state() {
+        autoreleasepool(|pool| {
+            let image = NSImage::from_system_symbol("circle.fill").unwrap();
+            for_each_item(pool, |item| {
+                let has_default = item.image_for_state(pool, MenuItemState::On).is_some();
+                item.set_image_for_state(MenuItemState::On, Some(&image));
+                assert_eq!(item.image_for_state(pool, MenuItemState::On), Some(&*image));
+                assert_ne!(
+                    item.image_for_state(pool, MenuItemState::Mixed),
+                    Some(&*image)
+                );
+                item.set_image_for_state(MenuItemState::On, None);
+                assert_ne!(item.image_for_state(pool, MenuItemState::On), Some(&*image));
+                assert_eq!(
+                    item.image_for_state(pool, MenuItemState::On).is_some(),
+                    has_default
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        autoreleasepool(|_| {
+            let mut menu = NSMenu::new();
+            let first = menu.add_item("item");
+            let second = menu.add_item("item");
+            // Items with the same title are different items
+            assert_ne!(first, second);
+
+            let mut items = HashSet::new();
+            assert!(items.insert(first.clone()));
+            assert!(items.insert(second));
+            assert!(!items.insert(first));
+            assert_eq!(items.len(), 2);
+        });
+    }
+
+    #[test]
+    fn test_highlighted() {
+        autoreleasepool(|pool| {
+            // Items are never highlighted outside of an open menu
+            for_each_item(pool, |item| assert!(!item.is_highlighted()));
+        });
+    }
+
+    #[test]
+    fn test_keeps_menu_open() {
+        use std::cell::Cell;
+
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                assert!(!item.keeps_menu_open());
+                item.set_keeps_menu_open(true);
+                assert!(item.keeps_menu_open());
+                assert!(item.view(pool).is_some());
+                item.set_keeps_menu_open(false);
+                assert!(!item.keeps_menu_open());
+                assert!(item.view(pool).is_none());
+            });
+
+            // Clicking the view sends the action
+            let count = Rc::new(Cell::new(0));
+            let mut item = NSMenuItem::new("item", "", None);
+            item.set_action({
+                let count = count.clone();
+                move || count.set(count.get() + 1)
+            });
+            item.set_keeps_menu_open(true);
+            let view = item.view(pool).unwrap();
+            let event: *const Object = core::ptr::null();
+            let _: () = unsafe { msg_send![view, mouseUp: event] };
+            assert_eq!(count.get(), 1);
+        });
+    }
+
+    #[test]
+    fn test_view() {
+        autoreleasepool(|pool| {
+            let view: Id<NSView, Shared> = unsafe {
+                let ptr: *mut NSView = msg_send![class!(NSView), alloc];
+                Id::new(msg_send![ptr, init]).unwrap()
+            };
+            for_each_item(pool, |item| {
+                assert!(item.view(pool).is_none());
+                item.set_view(Some(&view));
+                assert_eq!(item.view(pool), Some(&*view));
+                item.set_view(None);
+                assert!(item.view(pool).is_none());
+            });
+        });
+    }
+
+    #[test]
+    fn test_menu() {
+        autoreleasepool(|pool| {
+            let mut menu = NSMenu::new();
+            let item = NSMenuItem::new_empty();
+            assert!(item.menu(pool).is_none());
+            let item = menu.add(item);
+            assert_eq!(item.menu(pool), Some(&*menu));
+            menu.remove(&item);
+            assert!(item.menu(pool).is_none());
+        });
+    }
+
+    #[test]
+    fn test_alternate() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                assert!(!item.is_alternate());
+                item.set_alternate(true);
+                assert!(item.is_alternate());
+                item.set_alternate(false);
+                assert!(!item.is_alternate());
+            });
+        });
+    }
+
+    #[test]
+    fn test_badge() {
+        autoreleasepool(|pool| {
+            let available = Class::get("NSMenuItemBadge").is_some();
+            for_each_item(pool, |item| {
+                let badge = |item: &NSMenuItem| -> bool {
+                    if !available {
+                        return false;
+                    }
+                    let badge: *mut Object = unsafe { msg_send![item, badge] };
+                    !badge.is_null()
+                };
+                item.set_badge_count(Some(3));
+                assert_eq!(badge(item), available);
+                item.set_badge_count(None);
+                assert!(!badge(item));
+                item.set_badge_text(Some("New"));
+                assert_eq!(badge(item), available);
+                item.set_badge_text(None);
+                assert!(!badge(item));
+            });
+        });
+    }
+
+    #[test]
+    fn test_represented_object() {
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                assert!(item.represented_object(pool).is_none());
+                assert_eq!(item.represented_tag(), None);
+
+                item.set_represented_tag(42);
+                assert_eq!(item.represented_tag(), Some(42));
+                item.set_represented_tag(i64::MIN);
+                assert_eq!(item.represented_tag(), Some(i64::MIN));
+
+                let string = NSString::from_str("not a number");
+                item.set_represented_object(Some(&string));
+                assert!(item.represented_object(pool).is_some());
+                assert_eq!(item.represented_tag(), None);
+
+                item.set_represented_object(None);
+                assert!(item.represented_object(pool).is_none());
+            });
+        });
+    }
+
+    #[test]
+    fn test_action() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        autoreleasepool(|pool| {
+            for_each_item(pool, |item| {
+                let count = Rc::new(Cell::new(0));
+                let item_count = count.clone();
+                item.set_action(move || item_count.set(item_count.get() + 1));
+
+                let target: *mut Object = unsafe { msg_send![&*item, target] };
+                let action: Sel = unsafe { msg_send![&*item, action] };
+                let _: () =
+                    unsafe { msg_send![target, performSelector: action, withObject: &*item] };
+                assert_eq!(count.get(), 1);
+            });
+        });
+    }
+
+    #[test]
+    fn test_action_with_item() {
+        use std::cell::RefCell;
+
+        autoreleasepool(|_| {
+            let clicked = Rc::new(RefCell::new(Vec::new()));
+            let handler = {
+                let clicked = clicked.clone();
+                move |item: &NSMenuItem| clicked.borrow_mut().push(item.tag())
+            };
+            let mut menu = NSMenu::new();
+            for tag in 0..3 {
+                let mut item = NSMenuItem::new("item", "", None);
+                item.set_tag(tag);
+                item.set_action_with_item(handler.clone());
+                menu.add(item);
+            }
+
+            menu.perform_action_for_item_at(2);
+            menu.perform_action_for_item_at(0);
+            assert_eq!(*clicked.borrow(), [2, 0]);
+        });
+    }
+
+    #[test]
+    fn test_target_action() {
+        autoreleasepool(|pool| {
+            let target: Id<Object, Owned> = unsafe {
+                let ptr: *mut Object = msg_send![class!(NSObject), alloc];
+                Id::new(msg_send![ptr, init]).unwrap()
+            };
+            let mut item = NSMenuItem::new("item", "", None);
+            item.set_action(|| {});
+            item.set_target_action(Some(&target), sel!(description));
+            let item_target: *const Object = unsafe { msg_send![&*item, target] };
+            let action: Sel = unsafe { msg_send![&*item, action] };
+            assert_eq!(item_target, &*target as *const Object);
+            assert_eq!(action, sel!(description));
+
+            // Through the responder chain
+            item.set_target_action(None, sel!(copy:));
+            let item_target: *const Object = unsafe { msg_send![&*item, target] };
+            assert!(item_target.is_null());
+        });
+    }
+
+    #[test]
+    fn test_validator() {
+        use std::cell::Cell;
+
+        autoreleasepool(|_| {
+            let enabled = Rc::new(Cell::new(false));
+            let count = Rc::new(Cell::new(0));
+            let mut menu = NSMenu::new();
+            let mut item = NSMenuItem::new("item", "", None);
+            item.set_validator({
+                let enabled = enabled.clone();
+                move || enabled.get()
+            });
+            // Setting the action afterwards keeps the validator
+            item.set_action({
+                let count = count.clone();
+                move || count.set(count.get() + 1)
+            });
+            let item = menu.add(item);
+
+            menu.update();
+            assert!(!item.is_enabled());
+            enabled.set(true);
+            menu.update();
+            assert!(item.is_enabled());
+
+            menu.perform_action_for_item_at(0);
+            assert_eq!(count.get(), 1);
+        });
+    }
+
+    #[test]
+    fn test_disabled_action() {
+        use std::cell::Cell;
+
+        autoreleasepool(|_| {
+            let count = Rc::new(Cell::new(0));
+            let mut menu = NSMenu::new();
+            menu.set_autoenables_items(false);
+            let mut item = NSMenuItem::new("item", "", None);
+            item.set_action({
+                let count = count.clone();
+                move || count.set(count.get() + 1)
+            });
+            item.set_enabled(false);
+            menu.add(item);
+
+            menu.perform_action_for_item_at(0);
+            assert_eq!(count.get(), 0);
+        });
+    }
+
+    #[test]
+    fn test_invalid_action() {
+        use std::cell::Cell;
+
+        autoreleasepool(|_| {
+            let enabled = Rc::new(Cell::new(false));
+            let count = Rc::new(Cell::new(0));
+            let mut menu = NSMenu::new();
+            let mut item = NSMenuItem::new("item", "", None);
+            item.set_action({
+                let count = count.clone();
+                move || count.set(count.get() + 1)
+            });
+            item.set_validator({
+                let enabled = enabled.clone();
+                move || enabled.get()
+            });
+            menu.add(item);
+
+            // Validation fails, even without the menu having been updated
+            menu.perform_action_for_item_at(0);
+            assert_eq!(count.get(), 0);
+            enabled.set(true);
+            menu.perform_action_for_item_at(0);
+            assert_eq!(count.get(), 1);
+        });
+    }
+
+    #[test]
+    fn test_action_dropped() {
+        use std::rc::Rc;
+
+        let marker = Rc::new(());
+        autoreleasepool(|_| {
+            let mut item = NSMenuItem::new("", "", None);
+            let item_marker = marker.clone();
+            item.set_action(move || {
+                let _ = &item_marker;
+            });
+            assert_eq!(Rc::strong_count(&marker), 2);
+            // Replacing the action releases the old closure
+            item.set_action(|| {});
+            assert_eq!(Rc::strong_count(&marker), 1);
+        });
+    }
+
     #[test]
     fn test_submenu() {
         autoreleasepool(|pool| {
             for_each_item(pool, |item| {
                 assert!(item.submenu(pool).is_none());
+                assert!(!item.has_submenu());
                 let menu = NSMenu::new();
                 let menu = item.set_submenu(Some(menu));
                 assert_eq!(item.submenu(pool), menu.as_deref());
+                assert!(item.has_submenu());
                 item.set_submenu(None);
                 assert!(item.submenu(pool).is_none());
+                assert!(!item.has_submenu());
             })
         });
     }
+
+    #[test]
+    fn test_parent_item() {
+        autoreleasepool(|pool| {
+            let mut parent = NSMenuItem::new("parent", "", None);
+            let mut submenu = NSMenu::new();
+            let child = submenu.add(NSMenuItem::new("child", "", None));
+            assert!(child.parent_item(pool).is_none());
+            parent.set_submenu(Some(submenu));
+            assert_eq!(child.parent_item(pool), Some(&*parent));
+            assert!(parent.parent_item(pool).is_none());
+        });
+    }
 }
@@ -1,12 +1,58 @@
+mod activation;
+mod appearance;
+mod associated;
+mod attributed;
+mod availability;
+mod color;
+mod delegate;
+#[cfg(feature = "serde")]
+mod description;
+mod event;
+mod font;
 mod global;
+mod image;
+mod keepopen;
+mod keyequivalent;
+mod leaks;
+mod macros;
+mod mainthread;
 mod menu;
 mod menubar;
 mod menuitem;
+mod platform;
+mod statusbar;
+mod target;
+mod view;
+mod visibility;
+#[cfg(feature = "winit")]
+mod winit;
 
-pub use self::menubar::MenuBar;
+pub use self::menubar::{MainMenu, MenuBar};
+pub use activation::ActivationObserver;
+pub use appearance::Appearance;
+pub use attributed::AttributedStringBuilder;
+pub use color::NSColor;
+pub use delegate::{MenuDelegate, MenuObserver};
+#[cfg(feature = "serde")]
+pub use description::{ItemDescription, MenuDescription, ModifierDescription};
+pub use event::NSEvent;
+pub use font::NSFont;
 pub use global::InitializedApplication;
-pub use menu::NSMenu;
+pub use image::NSImage;
+pub use keyequivalent::{
+    format_key_equivalent, InvalidFunctionKeyError, KeyEquivalent, KeyModifiers,
+    ParseModifiersError,
+};
+#[cfg(feature = "leak-checks")]
+pub use leaks::report_leaks;
+pub use mainthread::MainThreadMarker;
+pub use menu::{MenuProperties, NSMenu};
 pub use menuitem::{MenuItemState, NSMenuItem};
+pub use statusbar::{NSStatusBar, NSStatusItem, StatusItemLength};
+pub use view::NSView;
+pub use visibility::MenubarVisibilityObserver;
+#[cfg(feature = "winit")]
+pub use winit::set_menubar_on_init;
 
 // We need the Objectice-C symbols like NSString, NSMenu and so on to be available
 #[link(name = "AppKit", kind = "framework")]
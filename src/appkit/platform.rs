@@ -0,0 +1,94 @@
+//! Implementations of the platform-agnostic traits.
+use objc2::rc::{Id, Shared};
+
+use super::menu::NSMenu;
+use super::menubar::MenuBar;
+use super::menuitem::NSMenuItem;
+use crate::platform;
+
+impl platform::Menubar for MenuBar {
+    type Menu = NSMenu;
+    type MenuHandle = Id<NSMenu, Shared>;
+
+    fn add(&mut self, title: &str, f: impl FnOnce(&mut NSMenu)) -> Id<NSMenu, Shared> {
+        self.add(title, f)
+    }
+}
+
+impl platform::Menu for NSMenu {
+    type Item = NSMenuItem;
+    type ItemHandle = Id<NSMenuItem, Shared>;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn add_item(&mut self, title: &str) -> Id<NSMenuItem, Shared> {
+        self.add_item(title)
+    }
+
+    fn add_separator(&mut self) -> Id<NSMenuItem, Shared> {
+        self.add_separator()
+    }
+
+    fn remove_all(&mut self) {
+        self.remove_all()
+    }
+}
+
+impl platform::MenuItem for NSMenuItem {
+    fn set_title(&mut self, title: &str) {
+        self.set_title(title)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.is_enabled()
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.set_enabled(enabled)
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.is_hidden()
+    }
+
+    fn set_hidden(&mut self, hidden: bool) {
+        self.set_hidden(hidden)
+    }
+
+    fn set_action(&mut self, handler: impl Fn() + 'static) {
+        self.set_action(handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2::rc::autoreleasepool;
+
+    use super::*;
+    use crate::platform::{Menu, Menubar};
+
+    fn build<M: Menubar>(menubar: &mut M) -> M::MenuHandle {
+        menubar.add("File", |menu| {
+            let _ = menu.add_item("Open");
+            let _ = menu.add_separator();
+            let _ = menu.add_item("Close");
+        })
+    }
+
+    #[test]
+    fn test_generic() {
+        autoreleasepool(|pool| {
+            let mut menubar = MenuBar::new(|_| {});
+            let file = build(&mut menubar);
+            assert_eq!(Menu::len(&*file), 3);
+            assert_eq!(file.item_at(pool, 2).unwrap().title(pool), "Close");
+            assert_eq!(menubar.title(1), "File");
+        });
+    }
+}
@@ -0,0 +1,185 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use objc2::rc::{autoreleasepool, AutoreleasePool, Id, Owned};
+use objc2::runtime::Object;
+use objc2::{class, msg_send, sel};
+use objc2::{Encoding, Message, RefEncode};
+use objc2_foundation::{CGFloat, NSString};
+
+use super::image::NSImage;
+use super::mainthread::MainThreadMarker;
+use super::menu::NSMenu;
+use super::view::NSView;
+
+/// The width of a status item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusItemLength {
+    /// Adjust the width to the item's contents.
+    #[doc(alias = "NSVariableStatusItemLength")]
+    Variable,
+    /// Make the width equal to the status bar's thickness.
+    #[doc(alias = "NSSquareStatusItemLength")]
+    Square,
+    /// A fixed width, in points.
+    Fixed(f64),
+}
+
+impl StatusItemLength {
+    fn to_raw(self) -> CGFloat {
+        match self {
+            Self::Variable => -1.0,
+            Self::Square => -2.0,
+            Self::Fixed(length) => length as CGFloat,
+        }
+    }
+}
+
+/// The system-wide status bar, the area on the right side of the menu bar
+/// containing e.g. the clock.
+#[repr(C)]
+pub struct NSStatusBar {
+    _priv: [u8; 0],
+    /// Makes the status bar `!Send` and `!Sync`, since it must only be used
+    /// from the main thread
+    _thread: PhantomData<*mut ()>,
+}
+
+unsafe impl RefEncode for NSStatusBar {
+    const ENCODING_REF: Encoding<'static> = Encoding::Object;
+}
+
+unsafe impl Message for NSStatusBar {}
+
+impl NSStatusBar {
+    /// The system status bar.
+    ///
+    /// The status bar is only usable from the main thread, hence the
+    /// marker.
+    #[doc(alias = "systemStatusBar")]
+    pub fn system(_mtm: MainThreadMarker) -> &'static Self {
+        unsafe { msg_send![class!(NSStatusBar), systemStatusBar] }
+    }
+
+    /// Add a new item to the status bar.
+    ///
+    /// The status bar doesn't keep the item alive, so it must be stored
+    /// somewhere for as long as it should be shown; see [`remove_item`].
+    ///
+    /// [`remove_item`]: Self::remove_item
+    #[doc(alias = "statusItemWithLength")]
+    #[doc(alias = "statusItemWithLength:")]
+    pub fn add_item(&self, length: StatusItemLength) -> Id<NSStatusItem, Owned> {
+        let length = length.to_raw();
+        unsafe {
+            let ptr: *mut NSStatusItem = msg_send![self, statusItemWithLength: length];
+            Id::retain_autoreleased(ptr).unwrap()
+        }
+    }
+
+    /// Remove an item from the status bar.
+    #[doc(alias = "removeStatusItem")]
+    #[doc(alias = "removeStatusItem:")]
+    pub fn remove_item(&self, item: Id<NSStatusItem, Owned>) {
+        unsafe { msg_send![self, removeStatusItem: &*item] }
+    }
+
+    /// The height of the status bar.
+    pub fn thickness(&self) -> f64 {
+        let thickness: CGFloat = unsafe { msg_send![self, thickness] };
+        thickness as f64
+    }
+}
+
+impl PartialEq for NSStatusBar {
+    /// Pointer equality
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NSStatusBar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSStatusBar")
+            .field("id", &(self as *const Self))
+            .finish()
+    }
+}
+
+/// An item in the [`NSStatusBar`], shown as a button that can open a menu.
+#[repr(C)]
+pub struct NSStatusItem {
+    _priv: [u8; 0],
+    /// Makes the item `!Send` and `!Sync`, like the status bar it is in
+    _thread: PhantomData<*mut ()>,
+}
+
+unsafe impl RefEncode for NSStatusItem {
+    const ENCODING_REF: Encoding<'static> = Encoding::Object;
+}
+
+unsafe impl Message for NSStatusItem {}
+
+impl NSStatusItem {
+    /// The button shown in the status bar, used to customize the item's
+    /// appearance.
+    pub fn button<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSView> {
+        unsafe { msg_send![self, button] }
+    }
+
+    fn button_ptr(&self) -> *mut Object {
+        unsafe { msg_send![self, button] }
+    }
+
+    /// The title shown in the button.
+    pub fn title(&self) -> String {
+        autoreleasepool(|pool| {
+            let title: Option<&NSString> = unsafe { msg_send![self.button_ptr(), title] };
+            title.map(|t| t.as_str(pool).to_owned()).unwrap_or_default()
+        })
+    }
+
+    /// Set the title shown in the button.
+    #[doc(alias = "setTitle")]
+    #[doc(alias = "setTitle:")]
+    pub fn set_title(&mut self, title: &str) {
+        let title = NSString::from_str(title);
+        unsafe { msg_send![self.button_ptr(), setTitle: &*title] }
+    }
+
+    /// Set the image shown in the button. `None` removes it.
+    #[doc(alias = "setImage")]
+    #[doc(alias = "setImage:")]
+    pub fn set_image(&mut self, image: Option<&NSImage>) {
+        unsafe { msg_send![self.button_ptr(), setImage: image] }
+    }
+
+    /// The menu shown when the item is clicked, if any.
+    pub fn menu<'p>(&self, pool: &'p AutoreleasePool) -> Option<&'p NSMenu> {
+        unsafe { msg_send![self, menu] }
+    }
+
+    /// Set the menu shown when the item is clicked. `None` removes it.
+    #[doc(alias = "setMenu")]
+    #[doc(alias = "setMenu:")]
+    pub fn set_menu(&mut self, menu: Option<&NSMenu>) {
+        unsafe { msg_send![self, setMenu: menu] }
+    }
+}
+
+impl PartialEq for NSStatusItem {
+    /// Pointer equality
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NSStatusItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSStatusItem")
+            .field("id", &(self as *const Self))
+            .finish()
+    }
+}
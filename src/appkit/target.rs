@@ -0,0 +1,130 @@
+use core::ffi::c_void;
+use std::rc::Rc;
+use std::sync::Once;
+
+use objc2::declare::ClassBuilder;
+use objc2::rc::{Id, Owned};
+use objc2::runtime::{Bool, Class, Object, Sel};
+use objc2::{class, msg_send, sel};
+
+use super::menuitem::NSMenuItem;
+
+const CLASS_NAME: &str = "MenubarActionTarget";
+const CALLBACKS_IVAR: &str = "_callbacks";
+
+/// Key used to associate the target with the menu item, so that it lives as
+/// long as the item does (menu item targets are not retained by AppKit).
+pub(super) static ASSOCIATION_KEY: u8 = 0;
+
+type Handler = Rc<dyn Fn(&NSMenuItem)>;
+type Validator = Rc<dyn Fn() -> bool>;
+
+/// The closures a target forwards to.
+///
+/// Targets are never modified once created; instead a new target is created
+/// with a copy of the callbacks. The closures are reference counted so that
+/// this is cheap, and so that a closure stays alive while it runs, even if
+/// the item's target is replaced from within it.
+#[derive(Clone, Default)]
+pub(super) struct Callbacks {
+    /// Called with the item that sent the action.
+    pub(super) handler: Option<Handler>,
+    pub(super) validator: Option<Validator>,
+}
+
+fn class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut builder = ClassBuilder::new(CLASS_NAME, class!(NSObject)).unwrap();
+        builder.add_ivar::<*mut c_void>(CALLBACKS_IVAR);
+        unsafe {
+            builder.add_method(
+                sel!(performAction:),
+                perform_action as extern "C" fn(&Object, Sel, *mut Object),
+            );
+            builder.add_method(
+                sel!(validateMenuItem:),
+                validate_menu_item as extern "C" fn(&Object, Sel, *mut NSMenuItem) -> Bool,
+            );
+            builder.add_method(sel!(dealloc), dealloc as extern "C" fn(&mut Object, Sel));
+        }
+        builder.register();
+    });
+    Class::get(CLASS_NAME).unwrap()
+}
+
+fn callbacks_ref(this: &Object) -> Option<&Callbacks> {
+    let callbacks: *mut c_void = unsafe { *this.ivar(CALLBACKS_IVAR) };
+    unsafe { callbacks.cast::<Callbacks>().as_ref() }
+}
+
+extern "C" fn perform_action(this: &Object, _cmd: Sel, sender: *mut Object) {
+    let sender = match unsafe { sender.as_ref() } {
+        Some(sender) => sender,
+        None => return,
+    };
+    let is_item: Bool = unsafe { msg_send![sender, isKindOfClass: class!(NSMenuItem)] };
+    if !is_item.is_true() {
+        return;
+    }
+    let item = unsafe { &*(sender as *const Object as *const NSMenuItem) };
+    let callbacks = match callbacks_ref(this) {
+        Some(callbacks) => callbacks.clone(),
+        None => return,
+    };
+    // Not every path that sends the action checks whether the item is
+    // enabled first, e.g. `performActionForItemAtIndex:` doesn't
+    if !item.is_enabled() {
+        return;
+    }
+    if let Some(validator) = &callbacks.validator {
+        if !validator() {
+            return;
+        }
+    }
+    if let Some(handler) = callbacks.handler {
+        handler(item);
+    }
+}
+
+extern "C" fn validate_menu_item(this: &Object, _cmd: Sel, _item: *mut NSMenuItem) -> Bool {
+    let validator = callbacks_ref(this).and_then(|c| c.validator.clone());
+    match validator {
+        Some(validator) => Bool::new(validator()),
+        None => Bool::YES,
+    }
+}
+
+extern "C" fn dealloc(this: &mut Object, _cmd: Sel) {
+    let callbacks: *mut c_void = unsafe { *this.ivar(CALLBACKS_IVAR) };
+    if !callbacks.is_null() {
+        drop(unsafe { Box::from_raw(callbacks.cast::<Callbacks>()) });
+    }
+    let _: () = unsafe { msg_send![super(this, class!(NSObject)), dealloc] };
+}
+
+/// Create a new target object that forwards the selector returned by
+/// [`action`] and menu item validation to the given callbacks.
+pub(super) fn new(callbacks: Callbacks) -> Id<Object, Owned> {
+    let callbacks = Box::new(callbacks);
+    let mut target: Id<Object, Owned> = unsafe {
+        let ptr: *mut Object = msg_send![class(), alloc];
+        Id::new(msg_send![ptr, init]).unwrap()
+    };
+    unsafe { target.set_ivar::<*mut c_void>(CALLBACKS_IVAR, Box::into_raw(callbacks).cast()) };
+    target
+}
+
+/// A copy of the callbacks of `target`, or `None` if it wasn't created with
+/// [`new`].
+pub(super) fn callbacks(target: &Object) -> Option<Callbacks> {
+    if !core::ptr::eq(target.class(), class()) {
+        return None;
+    }
+    callbacks_ref(target).cloned()
+}
+
+/// The selector the target responds to.
+pub(super) fn action() -> Sel {
+    sel!(performAction:)
+}
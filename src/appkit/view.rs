@@ -0,0 +1,37 @@
+use core::fmt;
+use objc2::{Encoding, Message, RefEncode};
+
+/// A thin wrapper around a view.
+///
+/// The only views this crate creates itself are the ones installed by
+/// [`NSMenuItem::set_keeps_menu_open`]; other references can be obtained by
+/// casting a pointer from another crate, e.g. the `ns_view` provided by
+/// `raw-window-handle`.
+///
+/// [`NSMenuItem::set_keeps_menu_open`]: super::NSMenuItem::set_keeps_menu_open
+#[repr(C)]
+pub struct NSView {
+    _priv: [u8; 0],
+}
+
+unsafe impl RefEncode for NSView {
+    const ENCODING_REF: Encoding<'static> = Encoding::Object;
+}
+
+unsafe impl Message for NSView {}
+
+impl PartialEq for NSView {
+    /// Pointer equality
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NSView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NSView")
+            .field("id", &(self as *const Self))
+            .finish()
+    }
+}
@@ -0,0 +1,150 @@
+//! Observing the visibility of the menu bar.
+use core::cell::Cell;
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr;
+use std::sync::Once;
+
+use objc2::declare::ClassBuilder;
+use objc2::ffi::NSUInteger;
+use objc2::rc::{Id, Owned};
+use objc2::runtime::{Class, Object, Sel};
+use objc2::{class, msg_send, sel};
+use objc2_foundation::NSString;
+
+const CLASS_NAME: &str = "MenubarVisibilityObserver";
+const CALLBACK_IVAR: &str = "_callback";
+const KEY_PATH: &str = "currentSystemPresentationOptions";
+
+/// `NSApplicationPresentationAutoHideMenuBar`
+const AUTO_HIDE_MENU_BAR: NSUInteger = 1 << 2;
+/// `NSApplicationPresentationHideMenuBar`
+const HIDE_MENU_BAR: NSUInteger = 1 << 3;
+/// `NSKeyValueObservingOptionNew`
+const OBSERVING_OPTION_NEW: NSUInteger = 1 << 0;
+
+struct Callback {
+    visible: Cell<bool>,
+    f: Box<dyn Fn(bool)>,
+}
+
+fn class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut builder = ClassBuilder::new(CLASS_NAME, class!(NSObject)).unwrap();
+        builder.add_ivar::<*mut c_void>(CALLBACK_IVAR);
+        unsafe {
+            builder.add_method(
+                sel!(observeValueForKeyPath:ofObject:change:context:),
+                observe_value
+                    as extern "C" fn(
+                        &Object,
+                        Sel,
+                        *mut Object,
+                        *mut Object,
+                        *mut Object,
+                        *mut c_void,
+                    ),
+            );
+            builder.add_method(sel!(dealloc), dealloc as extern "C" fn(&mut Object, Sel));
+        }
+        builder.register();
+    });
+    Class::get(CLASS_NAME).unwrap()
+}
+
+fn app() -> *mut Object {
+    unsafe { msg_send![class!(NSApplication), sharedApplication] }
+}
+
+fn menubar_visible() -> bool {
+    let options: NSUInteger = unsafe { msg_send![app(), currentSystemPresentationOptions] };
+    options & (AUTO_HIDE_MENU_BAR | HIDE_MENU_BAR) == 0
+}
+
+extern "C" fn observe_value(
+    this: &Object,
+    _cmd: Sel,
+    _key_path: *mut Object,
+    _object: *mut Object,
+    _change: *mut Object,
+    _context: *mut c_void,
+) {
+    let callback: *mut c_void = unsafe { *this.ivar(CALLBACK_IVAR) };
+    let callback = match unsafe { callback.cast::<Callback>().as_ref() } {
+        Some(callback) => callback,
+        None => return,
+    };
+    // The options can change without affecting the menu bar
+    let visible = menubar_visible();
+    if callback.visible.replace(visible) != visible {
+        (callback.f)(visible);
+    }
+}
+
+extern "C" fn dealloc(this: &mut Object, _cmd: Sel) {
+    let callback: *mut c_void = unsafe { *this.ivar(CALLBACK_IVAR) };
+    if !callback.is_null() {
+        drop(unsafe { Box::from_raw(callback.cast::<Callback>()) });
+    }
+    let _: () = unsafe { msg_send![super(this, class!(NSObject)), dealloc] };
+}
+
+/// Calls a closure when the visibility of the menu bar changes, until
+/// dropped.
+///
+/// Created with [`InitializedApplication::on_menubar_visibility_change`].
+///
+/// [`InitializedApplication::on_menubar_visibility_change`]: super::InitializedApplication::on_menubar_visibility_change
+#[must_use = "The callback is removed when the observer is dropped"]
+pub struct MenubarVisibilityObserver {
+    observer: Id<Object, Owned>,
+}
+
+impl MenubarVisibilityObserver {
+    pub(super) fn new(f: impl Fn(bool) + 'static) -> Self {
+        let callback = Box::new(Callback {
+            visible: Cell::new(menubar_visible()),
+            f: Box::new(f),
+        });
+        let mut observer: Id<Object, Owned> = unsafe {
+            let ptr: *mut Object = msg_send![class(), alloc];
+            Id::new(msg_send![ptr, init]).unwrap()
+        };
+        unsafe { observer.set_ivar::<*mut c_void>(CALLBACK_IVAR, Box::into_raw(callback).cast()) };
+
+        let key_path = NSString::from_str(KEY_PATH);
+        let context: *mut c_void = ptr::null_mut();
+        let _: () = unsafe {
+            msg_send![
+                app(),
+                addObserver: &*observer,
+                forKeyPath: &*key_path,
+                options: OBSERVING_OPTION_NEW,
+                context: context,
+            ]
+        };
+        Self { observer }
+    }
+}
+
+impl Drop for MenubarVisibilityObserver {
+    fn drop(&mut self) {
+        let key_path = NSString::from_str(KEY_PATH);
+        let _: () = unsafe {
+            msg_send![
+                app(),
+                removeObserver: &*self.observer,
+                forKeyPath: &*key_path,
+            ]
+        };
+    }
+}
+
+impl fmt::Debug for MenubarVisibilityObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MenubarVisibilityObserver")
+            .field("id", &(&*self.observer as *const Object))
+            .finish()
+    }
+}
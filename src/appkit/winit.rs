@@ -0,0 +1,66 @@
+//! Helpers for integrating with `winit`.
+use winit::event::{Event, StartCause};
+
+use super::global::InitializedApplication;
+use super::mainthread::MainThreadMarker;
+use super::menubar::{MainMenu, MenuBar};
+
+impl InitializedApplication {
+    /// Get the application from a `winit` event, if the event proves that
+    /// the application has finished launching.
+    ///
+    /// This is the case for [`StartCause::Init`], which `winit` emits from
+    /// `applicationDidFinishLaunching`. Other events, or calling this off
+    /// the main thread, return `None`.
+    ///
+    /// # Safety
+    ///
+    /// The event must have been received from the event loop, not
+    /// constructed manually, since only then does it prove that the
+    /// application has finished launching.
+    pub unsafe fn from_winit<T>(event: &Event<'_, T>) -> Option<&'static Self> {
+        match event {
+            Event::NewEvents(StartCause::Init) => {
+                let mtm = MainThreadMarker::new()?;
+                // SAFETY: `Init` is emitted after the application has
+                // launched, and the caller ensures it came from `winit`
+                Some(Self::new(mtm))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Set the menu bar when `winit` emits [`StartCause::Init`], which is the
+/// earliest point where the menu bar can be set.
+///
+/// Takes the menu bar out of `menubar` when it is set, so that this can be
+/// called unconditionally from the event loop callback.
+///
+/// # Safety
+///
+/// The event must have been received from the event loop, see
+/// [`InitializedApplication::from_winit`].
+///
+/// # Example
+///
+/// ```no_run
+/// use menubar::appkit::{set_menubar_on_init, MenuBar};
+/// use winit::event_loop::{ControlFlow, EventLoop};
+///
+/// let event_loop = EventLoop::new();
+/// let mut menubar = Some(MenuBar::new(|_| {}));
+/// event_loop.run(move |event, _, control_flow| {
+///     *control_flow = ControlFlow::Wait;
+///     // SAFETY: The event is from the event loop
+///     unsafe { set_menubar_on_init(&event, &mut menubar) };
+/// });
+/// ```
+pub unsafe fn set_menubar_on_init<T>(
+    event: &Event<'_, T>,
+    menubar: &mut Option<MenuBar>,
+) -> Option<MainMenu> {
+    let app = InitializedApplication::from_winit(event)?;
+    let menubar = menubar.take()?;
+    Some(app.set_menubar(menubar))
+}
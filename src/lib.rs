@@ -9,6 +9,11 @@
 
 #[cfg(target_os = "macos")]
 pub mod appkit;
+mod mnemonic;
+pub mod platform;
+
+pub use mnemonic::strip_mnemonics;
+pub use platform::{Menu, MenuItem, Menubar};
 
 #[cfg(test)]
 mod test_util;
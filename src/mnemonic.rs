@@ -0,0 +1,52 @@
+//! Helpers for menu titles shared between platforms.
+
+/// Remove Windows-style `&` mnemonics from a menu title, e.g. `"&File"`
+/// becomes `"File"`.
+///
+/// A doubled `&&` is an escaped ampersand, and becomes a single `&`. Useful
+/// for sharing menu definitions between platforms, since macOS doesn't
+/// support mnemonics and would display the ampersands.
+///
+/// # Example
+///
+/// ```
+/// use menubar::strip_mnemonics;
+///
+/// assert_eq!(strip_mnemonics("&File"), "File");
+/// assert_eq!(strip_mnemonics("Save &As…"), "Save As…");
+/// assert_eq!(strip_mnemonics("Fish && &Chips"), "Fish & Chips");
+/// ```
+pub fn strip_mnemonics(title: &str) -> String {
+    let mut result = String::with_capacity(title.len());
+    let mut chars = title.chars();
+    while let Some(c) = chars.next() {
+        if c == '&' {
+            match chars.next() {
+                Some(next) => result.push(next),
+                // A trailing ampersand doesn't mark anything
+                None => result.push('&'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_mnemonics() {
+        assert_eq!(strip_mnemonics(""), "");
+        assert_eq!(strip_mnemonics("File"), "File");
+        assert_eq!(strip_mnemonics("&File"), "File");
+        assert_eq!(strip_mnemonics("E&xit"), "Exit");
+        assert_eq!(strip_mnemonics("&&"), "&");
+        assert_eq!(strip_mnemonics("A&&B"), "A&B");
+        assert_eq!(strip_mnemonics("&&&X"), "&X");
+        assert_eq!(strip_mnemonics("Trailing&"), "Trailing&");
+        assert_eq!(strip_mnemonics("&Ünïcødé"), "Ünïcødé");
+    }
+}
@@ -0,0 +1,65 @@
+//! Platform-agnostic traits implemented by each backend's menu types.
+//!
+//! Code written against these traits works with any backend, while the
+//! backend types (e.g. `appkit::NSMenu`) additionally expose everything
+//! that is specific to their platform.
+//!
+//! The method names and signatures match the inherent methods of the
+//! backend types, so there is no difference in behavior between calling
+//! one or the other.
+
+use core::ops::Deref;
+
+/// The application's menu bar.
+pub trait Menubar {
+    type Menu: Menu;
+    /// A handle to a menu that has been added to the menu bar.
+    type MenuHandle: Deref<Target = Self::Menu>;
+
+    /// Create a top-level menu with the given title, fill it with `f`, and
+    /// append it to the menu bar.
+    fn add(&mut self, title: &str, f: impl FnOnce(&mut Self::Menu)) -> Self::MenuHandle;
+}
+
+/// A menu, either a top-level menu in the menu bar, a submenu or a context
+/// menu.
+pub trait Menu {
+    type Item: MenuItem;
+    /// A handle to an item that has been added to the menu.
+    type ItemHandle: Deref<Target = Self::Item>;
+
+    /// The number of items in the menu, including separators.
+    fn len(&self) -> usize;
+
+    /// Whether the menu has no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Create an item with the given title, and append it to the menu.
+    fn add_item(&mut self, title: &str) -> Self::ItemHandle;
+
+    /// Append a separator item to the menu.
+    fn add_separator(&mut self) -> Self::ItemHandle;
+
+    /// Remove all items from the menu.
+    fn remove_all(&mut self);
+}
+
+/// An item in a menu.
+pub trait MenuItem {
+    fn set_title(&mut self, title: &str);
+
+    /// Whether the item can be chosen, or is greyed out.
+    fn is_enabled(&self) -> bool;
+
+    fn set_enabled(&mut self, enabled: bool);
+
+    /// Whether the item is hidden from the menu.
+    fn is_hidden(&self) -> bool;
+
+    fn set_hidden(&mut self, hidden: bool);
+
+    /// Run `handler` whenever the item is chosen.
+    fn set_action(&mut self, handler: impl Fn() + 'static);
+}
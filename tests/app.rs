@@ -0,0 +1,292 @@
+//! Tests that need a launched application.
+//!
+//! AppKit must be launched and used from the main thread, but the default
+//! test harness runs every test on a worker thread. So this test is built
+//! with `harness = false`, and runs the tests one after another on the main
+//! thread itself. Since they share the application for the rest of the
+//! process, tests must restore any global state they change.
+
+#[cfg(target_os = "macos")]
+mod app {
+    use menubar::appkit::{
+        InitializedApplication, MainThreadMarker, MenuBar, NSMenu, NSStatusBar, StatusItemLength,
+    };
+    use objc2::rc::{autoreleasepool, Id, Shared};
+    use objc2::runtime::{Bool, Object};
+    use objc2::{class, msg_send};
+    use objc2_foundation::NSString;
+
+    /// Bring up a minimal application, to the point where
+    /// `applicationDidFinishLaunching` has been posted.
+    ///
+    /// Instead of running the application (which never returns), this
+    /// calls `finishLaunching` directly and then drains any events queued
+    /// while launching.
+    fn init_app(mtm: MainThreadMarker) -> &'static InitializedApplication {
+        autoreleasepool(|_| unsafe {
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let _: () = msg_send![app, finishLaunching];
+
+            let mode = NSString::from_str("kCFRunLoopDefaultMode");
+            let past: *mut Object = msg_send![class!(NSDate), distantPast];
+            loop {
+                let event: *mut Object = msg_send![
+                    app,
+                    nextEventMatchingMask: usize::MAX,
+                    untilDate: past,
+                    inMode: &*mode,
+                    dequeue: Bool::YES,
+                ];
+                if event.is_null() {
+                    break;
+                }
+                let _: () = msg_send![app, sendEvent: event];
+            }
+        });
+        // SAFETY: `finishLaunching` has been called above
+        unsafe { InitializedApplication::new(mtm) }
+    }
+
+    type Test = fn(&'static InitializedApplication);
+
+    pub fn main() {
+        let mtm = MainThreadMarker::new().expect("Tests must be run on the main thread");
+        let app = init_app(mtm);
+
+        // Run first, since it checks that no services menu has been set
+        let tests: &[(&str, Test)] = &[
+            ("test_services_menu", test_services_menu),
+            ("test_menubar_present", test_menubar_present),
+            ("test_menubar_height", test_menubar_height),
+            ("test_replace_menubar", test_replace_menubar),
+            ("test_main_menu", test_main_menu),
+            ("test_suppress_window_menu", test_suppress_window_menu),
+            ("test_suppress_help_menu", test_suppress_help_menu),
+            (
+                "test_menubar_visibility_observer",
+                test_menubar_visibility_observer,
+            ),
+            ("test_with_menubar_hidden", test_with_menubar_hidden),
+            ("test_activate", test_activate),
+            ("test_activation_observer", test_activation_observer),
+            ("test_status_item", test_status_item),
+        ];
+        let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+        let tests: Vec<_> = tests
+            .iter()
+            .filter(|(name, _)| match &filter {
+                Some(filter) => name.contains(filter.as_str()),
+                None => true,
+            })
+            .collect();
+        println!("\nrunning {} tests", tests.len());
+        for (name, test) in &tests {
+            print!("test {} ... ", name);
+            test(app);
+            println!("ok");
+        }
+        println!("\ntest result: ok. {} passed\n", tests.len());
+    }
+
+    fn test_services_menu(app: &InitializedApplication) {
+        let menu1 = NSMenu::new();
+        let menu2 = NSMenu::new();
+
+        autoreleasepool(|pool| {
+            assert!(app.services_menu(pool).is_none());
+
+            app.set_services_menu(&menu1);
+            assert_eq!(app.services_menu(pool).unwrap(), &*menu1);
+
+            app.set_services_menu(&menu2);
+            assert_eq!(app.services_menu(pool).unwrap(), &*menu2);
+
+            // At this point `menu1` still shows as a services menu...
+
+            // Setting the same menu again is fine
+            app.set_services_menu(&menu2);
+            assert_eq!(app.services_menu(pool).unwrap(), &*menu2);
+        });
+    }
+
+    fn test_menubar_present(app: &InitializedApplication) {
+        app.set_menubar(MenuBar::new(|_| {}));
+        assert!(app.menubar_present());
+        autoreleasepool(|pool| assert!(app.menubar(pool).is_some()));
+    }
+
+    fn test_menubar_height(app: &InitializedApplication) {
+        app.set_menubar(MenuBar::new(|_| {}));
+        assert!(app.menubar_height() > 0.0);
+    }
+
+    fn test_replace_menubar(app: &InitializedApplication) {
+        let first = app.set_menubar(MenuBar::new(|_| {}));
+        let second = app.replace_menubar(MenuBar::new(|_| {}));
+        autoreleasepool(|pool| {
+            assert_eq!(app.menubar(pool), Some(second.root()));
+            assert_ne!(app.menubar(pool), Some(first.root()));
+        });
+    }
+
+    fn test_main_menu(app: &InitializedApplication) {
+        let mut menubar = app.set_menubar(MenuBar::new(|_| {}));
+        let file = menubar.add("File", |_| {});
+        let _help = menubar.add("Help", |_| {});
+        menubar.insert(2, "Edit", |_| {});
+        menubar.set_title(1, "Document");
+        assert_eq!(menubar.titles(), ["", "Document", "Edit", "Help"]);
+        assert_eq!(menubar.menu(1), Some(file));
+
+        assert_eq!(menubar.remove(3).unwrap().title(), "Help");
+        autoreleasepool(|pool| {
+            let live = app.menubar(pool).unwrap();
+            assert_eq!(live, menubar.root());
+            assert_eq!(live.len(), 3);
+        });
+    }
+
+    fn test_suppress_window_menu(app: &InitializedApplication) {
+        // Both are changed for the whole process, so save them for restoring
+        let previous_tabbing: Bool =
+            unsafe { msg_send![class!(NSWindow), allowsAutomaticWindowTabbing] };
+        let registration_domain = NSString::from_str("NSRegistrationDomain");
+        let user_defaults: *mut Object =
+            unsafe { msg_send![class!(NSUserDefaults), standardUserDefaults] };
+        let previous_defaults: Id<Object, Shared> = unsafe {
+            let defaults: *mut Object =
+                msg_send![user_defaults, volatileDomainForName: &*registration_domain];
+            Id::new(msg_send![defaults, copy]).unwrap()
+        };
+
+        app.suppress_window_menu();
+        let menubar = app.set_menubar(MenuBar::new(|_| {}));
+        autoreleasepool(|pool| {
+            let window_menu = app.window_menu(pool).unwrap();
+            assert!(window_menu.is_empty());
+            assert!(window_menu.supermenu(pool).is_none());
+            assert!(menubar
+                .root()
+                .index_of_item_with_submenu(window_menu)
+                .is_none());
+        });
+        let tabbing: Bool = unsafe { msg_send![class!(NSWindow), allowsAutomaticWindowTabbing] };
+        assert!(!tabbing.is_true());
+
+        unsafe {
+            let _: () = msg_send![
+                class!(NSWindow),
+                setAllowsAutomaticWindowTabbing: previous_tabbing
+            ];
+            let _: () =
+                msg_send![user_defaults, removeVolatileDomainForName: &*registration_domain];
+            let _: () = msg_send![
+                user_defaults,
+                setVolatileDomain: &*previous_defaults,
+                forName: &*registration_domain,
+            ];
+        }
+    }
+
+    fn test_suppress_help_menu(app: &InitializedApplication) {
+        app.suppress_help_menu();
+        autoreleasepool(|pool| {
+            let help_menu = app.help_menu(pool).unwrap();
+            assert!(help_menu.supermenu(pool).is_none());
+        });
+    }
+
+    fn test_menubar_visibility_observer(app: &InitializedApplication) {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let observer = app.on_menubar_visibility_change({
+            let changes = changes.clone();
+            move |visible| changes.borrow_mut().push(visible)
+        });
+        app.set_menubar_visible(false);
+        app.set_menubar_visible(true);
+        drop(observer);
+        app.set_menubar_visible(false);
+        app.set_menubar_visible(true);
+        assert_eq!(*changes.borrow(), [false, true]);
+    }
+
+    fn test_with_menubar_hidden(app: &InitializedApplication) {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        app.set_menubar_visible(true);
+        let result = app.with_menubar_hidden(|| {
+            assert!(!app.menubar_visible());
+            42
+        });
+        assert_eq!(result, 42);
+        assert!(app.menubar_visible());
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            app.with_menubar_hidden(|| panic!("presentation failed"))
+        }));
+        assert!(result.is_err());
+        assert!(app.menubar_visible());
+    }
+
+    fn test_activate(app: &InitializedApplication) {
+        // Whether the application actually becomes active depends on the
+        // environment the tests are run in
+        app.activate(false);
+        app.activate(true);
+    }
+
+    fn test_activation_observer(app: &InitializedApplication) {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let observer = app.on_activation_change({
+            let changes = changes.clone();
+            move |active| changes.borrow_mut().push(active)
+        });
+        let post = || {
+            let center: *mut Object =
+                unsafe { msg_send![class!(NSNotificationCenter), defaultCenter] };
+            let name = NSString::from_str("NSApplicationDidBecomeActiveNotification");
+            let _: () = unsafe { msg_send![center, postNotificationName: &*name, object: app] };
+        };
+        post();
+        drop(observer);
+        post();
+        assert_eq!(*changes.borrow(), [app.is_active()]);
+    }
+
+    fn test_status_item(app: &InitializedApplication) {
+        let status_bar = NSStatusBar::system(app.main_thread_marker());
+        assert!(status_bar.thickness() > 0.0);
+
+        let mut item = status_bar.add_item(StatusItemLength::Variable);
+        autoreleasepool(|pool| {
+            assert!(item.button(pool).is_some());
+            assert!(item.menu(pool).is_none());
+        });
+
+        for &title in ["", "test", "abcαβγ", "🐱123"].iter() {
+            item.set_title(title);
+            assert_eq!(item.title(), title);
+        }
+
+        let menu = NSMenu::new();
+        item.set_menu(Some(&menu));
+        autoreleasepool(|pool| assert_eq!(item.menu(pool), Some(&*menu)));
+        item.set_menu(None);
+
+        status_bar.remove_item(item);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn main() {
+    app::main();
+}
+
+#[cfg(not(target_os = "macos"))]
+fn main() {}